@@ -0,0 +1,49 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use core::{
+    future::Future,
+    task::{Context, Poll},
+};
+use pin_utils::pin_mut;
+use std::{
+    sync::Arc,
+    task::{Wake, Waker},
+    thread,
+};
+
+/// Wakes up the thread running [`block_on`] by unparking it.
+struct ThreadWaker(thread::Thread);
+
+impl Wake for ThreadWaker {
+    #[inline]
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    #[inline]
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Drive a future to completion on the current thread, parking the thread
+/// while the future is pending.
+pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+    pin_mut!(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            // Spurious wake ups are harmless; the future is just polled again
+            Poll::Pending => thread::park(),
+        }
+    }
+}
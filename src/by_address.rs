@@ -0,0 +1,81 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use core::{
+    hash::{Hash, Hasher},
+    ops::Deref,
+    ptr::NonNull,
+};
+
+use crate::CryoMutReadGuard;
+
+/// A wrapper of a lock guard whose [`Hash`] and [`Eq`] implementations
+/// compare which cell the guard was borrowed from, rather than the
+/// referent's value. Created by [`CryoMutReadGuard::by_address`].
+///
+/// Guards projected from the same cell (e.g., by [`CryoMutReadGuard::map`])
+/// compare equal.
+///
+/// ```
+/// # use cryo::*;
+/// use std::collections::HashSet;
+///
+/// let (a, b) = (1, 1);
+/// with_cryo(&a, |cryo_a| {
+///     with_cryo(&b, |cryo_b| {
+///         let set: HashSet<_> = vec![cryo_a.borrow(), cryo_a.borrow(), cryo_b.borrow()]
+///             .into_iter()
+///             .map(CryoRef::by_address)
+///             .collect();
+///         assert_eq!(set.len(), 2);
+///     });
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct ByAddress<G>(pub G);
+
+impl<G> ByAddress<G> {
+    /// Unwrap the guard.
+    #[inline]
+    pub fn into_inner(self) -> G {
+        self.0
+    }
+}
+
+impl<G> Deref for ByAddress<G> {
+    type Target = G;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> ByAddress<CryoMutReadGuard<T, Lock>> {
+    #[inline]
+    fn cell_address(&self) -> NonNull<Lock> {
+        self.0.lock
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> PartialEq for ByAddress<CryoMutReadGuard<T, Lock>> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cell_address() == other.cell_address()
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> Eq for ByAddress<CryoMutReadGuard<T, Lock>> {}
+
+impl<T: ?Sized, Lock: crate::Lock> Hash for ByAddress<CryoMutReadGuard<T, Lock>> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cell_address().hash(state);
+    }
+}
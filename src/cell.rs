@@ -0,0 +1,400 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    marker::{PhantomData, PhantomPinned},
+    mem::ManuallyDrop,
+    pin::Pin,
+    ptr::{self, NonNull},
+};
+
+use crate::{CryoMutReadGuard, CryoMutWriteGuard};
+
+/// A variation of [`CryoMut`] that owns its contents.
+///
+/// Unlike `CryoMut`, `CryoCell` is not bound to a lifetime, so it can be
+/// constructed safely. Like `CryoMut`, it must be pinned to be borrowed,
+/// and when it's dropped, the current thread's execution will be prevented
+/// from moving forward (at least) until all references to it are dropped.
+///
+/// [`CryoMut`]: crate::CryoMut
+pub struct CryoCell<T: ?Sized, Lock: crate::Lock> {
+    lock: Lock,
+    _phantom: PhantomPinned,
+    value: UnsafeCell<T>,
+}
+
+/// `CryoCell` may be moved around multiple threads (e.g., by sending a
+/// `Pin<Box<CryoCell>>`), and on each thread [`CryoMutReadGuard`] may be
+/// created, forming multiple instances of `&T`. It also owns `T`. Therefore
+/// `CryoCell: Send` necessitates `T: Send + Sync`.
+///
+/// The requirements on `Lock` are identical to those of [`CryoMut`].
+///
+/// [`CryoMut`]: crate::CryoMut
+unsafe impl<T: ?Sized + Send + Sync, Lock: crate::Lock> Send for CryoCell<T, Lock>
+where
+    Lock: Send + Sync,
+    Lock: crate::lock::sealed::LockMarkerSend,
+{
+}
+
+/// `&mut T` may be created from `&CryoCell`, so sending `&CryoCell` to another
+/// thread requires `T: Send`.
+///
+/// `Lock::LockMarker: Send` is required because `Lock::lock_` may be called
+/// through an `&CryoCell` sent to another thread.
+unsafe impl<T: ?Sized + Send + Sync, Lock: crate::Lock> Sync for CryoCell<T, Lock>
+where
+    Lock: Sync,
+    Lock: crate::lock::sealed::LockMarkerSend,
+{
+}
+
+impl<T, Lock: crate::Lock> CryoCell<T, Lock> {
+    /// Construct a new `CryoCell`.
+    #[inline]
+    pub fn new(x: T) -> Self {
+        Self {
+            lock: Lock::new(),
+            _phantom: PhantomPinned,
+            value: UnsafeCell::new(x),
+        }
+    }
+
+    /// Consume the `CryoCell`, returning the contents.
+    ///
+    /// This never blocks or panics. Guards can only be created through
+    /// `Pin<&Self>`, and a pinned `CryoCell` can't be moved, so no guards
+    /// can exist when `self` is passed by value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+        // Safety: `this` is never used again and its destructor is never
+        //         called, so each field is moved out exactly once
+        unsafe {
+            drop(ptr::read(&this.lock));
+            ptr::read(&this.value).into_inner()
+        }
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> CryoCell<T, Lock> {
+    #[inline]
+    fn data(&self) -> NonNull<T> {
+        // Safety: `UnsafeCell::get` never returns a null pointer
+        unsafe { NonNull::new_unchecked(self.value.get()) }
+    }
+
+    /// Construct a [`CryoMutReadGuard`] for a shared lock held by the caller.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own a shared lock on `self.lock`, which will be
+    /// transferred to the returned guard. `self` must be pinned.
+    #[inline]
+    unsafe fn read_guard(&self) -> CryoMutReadGuard<T, Lock> {
+        CryoMutReadGuard {
+            data: self.data(),
+            lock: NonNull::from(&self.lock),
+        }
+    }
+
+    /// Construct a [`CryoMutWriteGuard`] for an exclusive lock held by the
+    /// caller.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own an exclusive lock on `self.lock`, which will be
+    /// transferred to the returned guard. `self` must be pinned.
+    #[inline]
+    unsafe fn write_guard(&self) -> CryoMutWriteGuard<T, Lock> {
+        CryoMutWriteGuard {
+            data: self.data(),
+            lock: NonNull::from(&self.lock),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Acquire a read (shared) lock on a `CryoCell`.
+    #[inline]
+    pub fn read(self: Pin<&Self>) -> CryoMutReadGuard<T, Lock> {
+        // Safety: `&CryoCell`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { self.lock.lock_shared() };
+        unsafe { self.read_guard() }
+    }
+
+    /// Attempt to acquire a read (shared) lock on a `CryoCell`.
+    #[inline]
+    pub fn try_read(self: Pin<&Self>) -> Option<CryoMutReadGuard<T, Lock>> {
+        // Safety: `&CryoCell`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        if unsafe { self.lock.try_lock_shared() } {
+            Some(unsafe { self.read_guard() })
+        } else {
+            None
+        }
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoCell`.
+    #[inline]
+    pub fn write(self: Pin<&Self>) -> CryoMutWriteGuard<T, Lock> {
+        // Safety: `&CryoCell`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { self.lock.lock_exclusive() };
+        unsafe { self.write_guard() }
+    }
+
+    /// Attempt to acquire a write (exclusive) lock on a `CryoCell`.
+    #[inline]
+    pub fn try_write(self: Pin<&Self>) -> Option<CryoMutWriteGuard<T, Lock>> {
+        // Safety: `&CryoCell`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        if unsafe { self.lock.try_lock_exclusive() } {
+            Some(unsafe { self.write_guard() })
+        } else {
+            None
+        }
+    }
+
+    /// Get a copy of the contents, holding a read (shared) lock while copying.
+    ///
+    /// This mirrors [`Cell::get`](core::cell::Cell::get). Unlike
+    /// [`Self::read`], the `CryoCell` doesn't have to be pinned because the
+    /// lock is released before this method returns.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// let cell = CryoCell::<_, LocalLock>::new(1);
+    /// cell.set(2);
+    /// assert_eq!(cell.get(), 2);
+    /// ```
+    #[inline]
+    pub fn get(&self) -> T
+    where
+        T: Copy,
+    {
+        // Safety: `&CryoCell`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { self.lock.lock_shared() };
+        // Safety: The shared lock excludes writers. Copying can't unwind.
+        let value = unsafe { *self.value.get() };
+        unsafe { self.lock.unlock_shared() };
+        value
+    }
+
+    /// Overwrite the contents, holding a write (exclusive) lock while
+    /// writing.
+    ///
+    /// This mirrors [`Cell::set`](core::cell::Cell::set). See [`Self::get`].
+    ///
+    /// On `Pin<&mut CryoCell>`, this is shadowed by [`Pin::set`]. Call it
+    /// through [`Pin::as_ref`] instead.
+    #[inline]
+    pub fn set(&self, value: T)
+    where
+        T: Copy,
+    {
+        // Safety: `&CryoCell`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { self.lock.lock_exclusive() };
+        // Safety: The exclusive lock excludes other accesses. `T: Copy`
+        //         doesn't have drop glue, so this can't unwind.
+        unsafe { *self.value.get() = value };
+        unsafe { self.lock.unlock_exclusive() };
+    }
+
+    /// Mutably borrow the contents using compile-time lifetime rules.
+    ///
+    /// Outstanding guards can only be created through `Pin<&Self>`, and a
+    /// pinned `CryoCell` can't be mutably borrowed, so this never fails.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Get a reference to the underlying [`Lock`], e.g., to read the counters
+    /// of an [`InstrumentedLock`].
+    ///
+    /// [`Lock`]: crate::Lock
+    /// [`InstrumentedLock`]: crate::InstrumentedLock
+    #[inline]
+    pub fn raw_lock(&self) -> &Lock {
+        &self.lock
+    }
+
+    /// Acquire a read (shared) lock on a `CryoCell` stored in an [`Arc`].
+    ///
+    /// The returned guard keeps a clone of the `Arc`, so the `CryoCell` is
+    /// not dropped while the guard exists.
+    ///
+    /// Dropping the guard may drop the `CryoCell`, so the guard can be sent to
+    /// another thread only if the `CryoCell` can. This excludes [`SyncLock`],
+    /// which can only be locked by the creator thread.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// use std::{pin::Pin, sync::Arc, thread::spawn};
+    ///
+    /// let cell: Pin<Arc<CryoCell<_, AtomicLock>>> = Arc::pin(CryoCell::new(42));
+    /// let borrow = cell.read_arc();
+    /// drop(cell);
+    /// spawn(move || assert_eq!(*borrow, 42)).join().unwrap();
+    /// ```
+    ///
+    /// [`Arc`]: alloc::sync::Arc
+    /// [`SyncLock`]: crate::SyncLock
+    #[cfg(all(feature = "alloc", feature = "atomic"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", feature = "atomic"))))]
+    #[inline]
+    pub fn read_arc(self: &Pin<alloc::sync::Arc<Self>>) -> ArcCryoRef<T, Lock> {
+        ArcCryoRef {
+            guard: self.as_ref().read(),
+            cell: Pin::clone(self),
+        }
+    }
+}
+
+impl<T: Default, Lock: crate::Lock> Default for CryoCell<T, Lock> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for CryoCell<T, Lock> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Safety: The constructed `CryoMutReadGuard` doesn't outlive `self`, so
+        //         `CryoMutReadGuard::{data, lock}` won't get dangling.
+        let this = unsafe { Pin::new_unchecked(self) };
+        if let Some(x) = this.try_read() {
+            f.debug_struct("CryoCell").field("data", &&*x).finish()
+        } else {
+            struct LockedPlaceholder;
+            impl fmt::Debug for LockedPlaceholder {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("<locked>")
+                }
+            }
+            f.debug_struct("CryoCell")
+                .field("data", &LockedPlaceholder)
+                .finish()
+        }
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> Drop for CryoCell<T, Lock> {
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: `&CryoCell`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { crate::seal_lock(&self.lock, "CryoCell") };
+        // A write lock ensures there are no other references to
+        // the contents
+    }
+}
+
+/// A read lock guard of a [`CryoCell`] that also keeps the `CryoCell` alive.
+/// Created by [`CryoCell::read_arc`].
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", feature = "atomic"))))]
+pub struct ArcCryoRef<T: ?Sized, Lock: crate::Lock> {
+    // Fields are dropped in declaration order. The lock must be released
+    // before the `CryoCell` is dropped.
+    guard: CryoMutReadGuard<T, Lock>,
+    cell: Pin<alloc::sync::Arc<CryoCell<T, Lock>>>,
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+impl<T: ?Sized, Lock: crate::Lock> core::ops::Deref for ArcCryoRef<T, Lock> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+impl<T: ?Sized, Lock: crate::Lock> Clone for ArcCryoRef<T, Lock> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            guard: self.guard.clone(),
+            cell: Pin::clone(&self.cell),
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+impl<T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for ArcCryoRef<T, Lock> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArcCryoRef")
+            .field("data", &&**self)
+            .finish()
+    }
+}
+
+/// A reference-counted [`CryoCell`]. Borrows can be made from any clone, and
+/// the `CryoCell` is dropped when the last `ArcCryo` or [`ArcCryoRef`] is
+/// dropped.
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", feature = "atomic"))))]
+pub struct ArcCryo<T: ?Sized, Lock: crate::Lock> {
+    cell: Pin<alloc::sync::Arc<CryoCell<T, Lock>>>,
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+impl<T, Lock: crate::Lock> ArcCryo<T, Lock> {
+    /// Construct a new `ArcCryo`.
+    #[inline]
+    pub fn new(x: T) -> Self {
+        Self {
+            cell: alloc::sync::Arc::pin(CryoCell::new(x)),
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+impl<T: ?Sized, Lock: crate::Lock> ArcCryo<T, Lock> {
+    /// Borrow the cell. The returned guard keeps the cell alive.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// let cryo: ArcCryo<_, LocalLock> = ArcCryo::new(42);
+    /// let borrow = cryo.clone().borrow();
+    /// drop(cryo);
+    /// assert_eq!(*borrow, 42);
+    /// ```
+    #[inline]
+    pub fn borrow(&self) -> ArcCryoRef<T, Lock> {
+        self.cell.read_arc()
+    }
+
+    /// Get the underlying [`CryoCell`], e.g., to acquire a write lock.
+    #[inline]
+    pub fn as_cell(&self) -> Pin<&CryoCell<T, Lock>> {
+        self.cell.as_ref()
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+impl<T: ?Sized, Lock: crate::Lock> Clone for ArcCryo<T, Lock> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            cell: Pin::clone(&self.cell),
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+impl<T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for ArcCryo<T, Lock> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArcCryo")
+            .field("cell", &&*self.cell)
+            .finish()
+    }
+}
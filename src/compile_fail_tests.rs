@@ -0,0 +1,217 @@
+//! Compile-fail tests for the `Send`/`Sync`-ness and variance of the types in
+//! this crate and for compile-time checks.
+//!
+//! A `Lock` with a `!Sync` field can't make `Cryo` `Sync`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! # use std::cell::Cell;
+//! struct NotSyncLock(Cell<usize>);
+//! unsafe impl Lock for NotSyncLock {
+//!     type LockMarker = SendMarker;
+//!     type UnlockMarker = SendMarker;
+//!     const ON_CONTENTION: Contention = Contention::Block;
+//!     fn new() -> Self { Self(Cell::new(0)) }
+//!     unsafe fn lock_shared(&self) {}
+//!     unsafe fn try_lock_shared(&self) -> bool { true }
+//!     unsafe fn unlock_shared(&self) {}
+//!     unsafe fn lock_exclusive(&self) {}
+//!     unsafe fn try_lock_exclusive(&self) -> bool { true }
+//!     unsafe fn unlock_exclusive(&self) {}
+//! }
+//! fn assert_sync<T: Sync>() {}
+//! assert_sync::<Cryo<'static, u32, NotSyncLock>>();
+//! ```
+//!
+//! Nor `Send`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! # use std::cell::Cell;
+//! struct NotSyncLock(Cell<usize>);
+//! unsafe impl Lock for NotSyncLock {
+//!     type LockMarker = SendMarker;
+//!     type UnlockMarker = SendMarker;
+//!     const ON_CONTENTION: Contention = Contention::Block;
+//!     fn new() -> Self { Self(Cell::new(0)) }
+//!     unsafe fn lock_shared(&self) {}
+//!     unsafe fn try_lock_shared(&self) -> bool { true }
+//!     unsafe fn unlock_shared(&self) {}
+//!     unsafe fn lock_exclusive(&self) {}
+//!     unsafe fn try_lock_exclusive(&self) -> bool { true }
+//!     unsafe fn unlock_exclusive(&self) {}
+//! }
+//! fn assert_send<T: Send>() {}
+//! assert_send::<Cryo<'static, u32, NotSyncLock>>();
+//! ```
+//!
+//! The same goes for `CryoMut`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! # use std::cell::Cell;
+//! struct NotSyncLock(Cell<usize>);
+//! unsafe impl Lock for NotSyncLock {
+//!     type LockMarker = SendMarker;
+//!     type UnlockMarker = SendMarker;
+//!     const ON_CONTENTION: Contention = Contention::Block;
+//!     fn new() -> Self { Self(Cell::new(0)) }
+//!     unsafe fn lock_shared(&self) {}
+//!     unsafe fn try_lock_shared(&self) -> bool { true }
+//!     unsafe fn unlock_shared(&self) {}
+//!     unsafe fn lock_exclusive(&self) {}
+//!     unsafe fn try_lock_exclusive(&self) -> bool { true }
+//!     unsafe fn unlock_exclusive(&self) {}
+//! }
+//! fn assert_sync<T: Sync>() {}
+//! assert_sync::<CryoMut<'static, u32, NotSyncLock>>();
+//! ```
+//!
+//! A guard can be sent to another thread only if the lock is `Sync` because
+//! the guard accesses the lock through a shared reference:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! # use std::cell::Cell;
+//! struct NotSyncLock(Cell<usize>);
+//! unsafe impl Lock for NotSyncLock {
+//!     type LockMarker = SendMarker;
+//!     type UnlockMarker = SendMarker;
+//!     const ON_CONTENTION: Contention = Contention::Block;
+//!     fn new() -> Self { Self(Cell::new(0)) }
+//!     unsafe fn lock_shared(&self) {}
+//!     unsafe fn try_lock_shared(&self) -> bool { true }
+//!     unsafe fn unlock_shared(&self) {}
+//!     unsafe fn lock_exclusive(&self) {}
+//!     unsafe fn try_lock_exclusive(&self) -> bool { true }
+//!     unsafe fn unlock_exclusive(&self) {}
+//! }
+//! fn assert_send<T: Send>() {}
+//! assert_send::<CryoRef<u32, NotSyncLock>>();
+//! ```
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! # use std::cell::Cell;
+//! struct NotSyncLock(Cell<usize>);
+//! unsafe impl Lock for NotSyncLock {
+//!     type LockMarker = SendMarker;
+//!     type UnlockMarker = SendMarker;
+//!     const ON_CONTENTION: Contention = Contention::Block;
+//!     fn new() -> Self { Self(Cell::new(0)) }
+//!     unsafe fn lock_shared(&self) {}
+//!     unsafe fn try_lock_shared(&self) -> bool { true }
+//!     unsafe fn unlock_shared(&self) {}
+//!     unsafe fn lock_exclusive(&self) {}
+//!     unsafe fn try_lock_exclusive(&self) -> bool { true }
+//!     unsafe fn unlock_exclusive(&self) {}
+//! }
+//! fn assert_send<T: Send>() {}
+//! assert_send::<CryoMutWriteGuard<u32, NotSyncLock>>();
+//! ```
+//!
+//! `LocalLock` doesn't implement `SendableGuard` or `SendableCryo`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! fn assert_sendable_guard<L: SendableGuard>() {}
+//! assert_sendable_guard::<LocalLock>();
+//! ```
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! fn assert_sendable_cryo<L: SendableCryo>() {}
+//! assert_sendable_cryo::<LocalLock>();
+//! ```
+//!
+//! `CryoMutWriteGuard` is invariant over `T`. Otherwise, a short-lived
+//! reference could be written through it into a long-lived location:
+//!
+//! ```compile_fail
+//! # use cryo::*;
+//! fn shorten<'a>(
+//!     x: CryoMutWriteGuard<&'static str, LocalLock>,
+//! ) -> CryoMutWriteGuard<&'a str, LocalLock> {
+//!     x
+//! }
+//! ```
+//!
+//! `CryoMutReadGuard::project_array` rejects an out-of-range index:
+//!
+//! ```compile_fail,E0080
+//! # use cryo::*;
+//! with_cryo(&[1u32, 2, 3, 4], |cryo| {
+//!     cryo.borrow().project_array::<4>();
+//! });
+//! ```
+//!
+//! `CryoMutWriteGuard` is `!UnwindSafe`, so it can't cross `catch_unwind`
+//! without `AssertUnwindSafe`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! with_cryo((&mut 1, lock_ty::<AtomicLock>()), |cryo_mut| {
+//!     let mut guard = cryo_mut.write();
+//!     let _ = std::panic::catch_unwind(move || *guard = 2);
+//! });
+//! ```
+//!
+//! `CryoWaker<T>` isn't `Send` unless `T: Sync`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! # use std::cell::Cell;
+//! fn assert_send<T: Send>() {}
+//! assert_send::<CryoWaker<Cell<u32>>>();
+//! ```
+//!
+//! The `Send`/`Sync` matrix per `Lock` type (see `tests/test_send_sync.rs`
+//! for the positive cases). `LocalLock` guards aren't `Send`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! fn assert_send<T: Send>() {}
+//! assert_send::<CryoRef<u32, LocalLock>>();
+//! ```
+//!
+//! Nor are `LocalLock` cells:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! fn assert_send<T: Send>() {}
+//! assert_send::<Cryo<'static, u32, LocalLock>>();
+//! ```
+//!
+//! `SyncLock` cells are neither `Sync`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! fn assert_sync<T: Sync>() {}
+//! assert_sync::<Cryo<'static, u32, SyncLock>>();
+//! ```
+//!
+//! Nor `Send`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! fn assert_send<T: Send>() {}
+//! assert_send::<CryoMut<'static, u32, SyncLock>>();
+//! ```
+//!
+//! A `CryoRef<T, _>` isn't `Send` unless `T: Sync`, regardless of `Lock`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! # use std::cell::Cell;
+//! fn assert_send<T: Send>() {}
+//! assert_send::<CryoRef<Cell<u32>, AtomicLock>>();
+//! ```
+//!
+//! A `CryoMutWriteGuard<T, _>` isn't `Send` unless `T: Send`:
+//!
+//! ```compile_fail,E0277
+//! # use cryo::*;
+//! # use std::rc::Rc;
+//! fn assert_send<T: Send>() {}
+//! assert_send::<CryoMutWriteGuard<Rc<u32>, AtomicLock>>();
+//! ```
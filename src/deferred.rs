@@ -0,0 +1,72 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use core::{fmt, mem::ManuallyDrop, ops::Deref};
+
+use crate::CryoMutReadGuard;
+
+/// A [`CryoMutReadGuard`] that calls a given function after releasing the
+/// shared lock. Created by [`CryoMutReadGuard::defer_release`].
+///
+/// The function is called strictly after the lock is released, so it can
+/// observe the cell as no longer borrowed by this guard.
+///
+/// ```
+/// # use cryo::*;
+/// with_cryo(&42, |cryo| {
+///     let borrow = cryo.borrow().defer_release(|| {
+///         assert!(!cryo.would_block_on_seal());
+///     });
+///     assert_eq!(**borrow, 42);
+/// });
+/// ```
+#[must_use = "the shared lock is released immediately if the guard is dropped"]
+pub struct DeferredRelease<T: ?Sized, Lock: crate::Lock, F: FnOnce()> {
+    guard: ManuallyDrop<CryoMutReadGuard<T, Lock>>,
+    on_release: ManuallyDrop<F>,
+}
+
+impl<T: ?Sized, Lock: crate::Lock, F: FnOnce()> DeferredRelease<T, Lock, F> {
+    #[inline]
+    pub(crate) fn new(guard: CryoMutReadGuard<T, Lock>, on_release: F) -> Self {
+        Self {
+            guard: ManuallyDrop::new(guard),
+            on_release: ManuallyDrop::new(on_release),
+        }
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock, F: FnOnce()> Deref for DeferredRelease<T, Lock, F> {
+    type Target = CryoMutReadGuard<T, Lock>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, Lock: crate::Lock, F: FnOnce()> fmt::Debug
+    for DeferredRelease<T, Lock, F>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeferredRelease")
+            .field("guard", &*self.guard)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock, F: FnOnce()> Drop for DeferredRelease<T, Lock, F> {
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: These fields are never touched again
+        let on_release = unsafe { ManuallyDrop::take(&mut self.on_release) };
+        unsafe { ManuallyDrop::drop(&mut self.guard) };
+        on_release();
+    }
+}
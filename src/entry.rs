@@ -0,0 +1,53 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+
+/// The trait for map-like types providing an `entry` API. Used by
+/// [`CryoMut::with_entry`].
+///
+/// [`CryoMut::with_entry`]: crate::CryoMut::with_entry
+///
+/// The last type parameter only exists to limit `'a` to the lifetimes for
+/// which `&'a mut Self` is valid, so that `T: for<'a> EntryMap<'a, K>` doesn't
+/// require `T: 'static`. Leave it to its default.
+pub trait EntryMap<'a, K, ImplicitBounds = &'a Self> {
+    /// The entry type, e.g., [`hash_map::Entry`].
+    ///
+    /// [`hash_map::Entry`]: std::collections::hash_map::Entry
+    type Entry;
+
+    /// Get the entry for `key` for in-place manipulation.
+    fn entry(&'a mut self, key: K) -> Self::Entry;
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<'a, K, V, S> EntryMap<'a, K> for std::collections::HashMap<K, V, S>
+where
+    K: Eq + core::hash::Hash,
+    S: core::hash::BuildHasher,
+{
+    type Entry = std::collections::hash_map::Entry<'a, K, V>;
+
+    #[inline]
+    fn entry(&'a mut self, key: K) -> Self::Entry {
+        self.entry(key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<'a, K: Ord, V> EntryMap<'a, K> for alloc::collections::BTreeMap<K, V> {
+    type Entry = alloc::collections::btree_map::Entry<'a, K, V>;
+
+    #[inline]
+    fn entry(&'a mut self, key: K) -> Self::Entry {
+        self.entry(key)
+    }
+}
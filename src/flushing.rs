@@ -0,0 +1,109 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use core::{
+    fmt,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+};
+use std::io;
+
+use crate::{CryoMut, CryoMutWriteGuard};
+
+/// A write lock guard of [`CryoMut`] that flushes the referent before
+/// releasing the lock. Created by [`CryoMut::write_flushing`].
+///
+/// An error from the flush on drop is ignored. Use [`Self::finish`] to
+/// observe it.
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct FlushingWriteGuard<T: ?Sized + io::Write, Lock: crate::Lock> {
+    guard: ManuallyDrop<CryoMutWriteGuard<T, Lock>>,
+}
+
+impl<'a, T: ?Sized + io::Write + 'a, Lock: crate::Lock> CryoMut<'a, T, Lock> {
+    /// Acquire a write (exclusive) lock on a `CryoMut`, returning a guard that
+    /// calls [`io::Write::flush`] before releasing the lock.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// use std::io::{BufWriter, Write};
+    ///
+    /// let mut writer = BufWriter::new(Vec::new());
+    /// with_cryo(&mut writer, |cryo_mut| {
+    ///     write!(cryo_mut.write_flushing(), "hello").unwrap();
+    /// });
+    /// assert_eq!(writer.get_ref(), b"hello");
+    /// ```
+    #[inline]
+    pub fn write_flushing(self: Pin<&Self>) -> FlushingWriteGuard<T, Lock> {
+        FlushingWriteGuard {
+            guard: ManuallyDrop::new(self.write()),
+        }
+    }
+}
+
+impl<T: ?Sized + io::Write, Lock: crate::Lock> FlushingWriteGuard<T, Lock> {
+    /// Flush the referent and release the lock, returning the result of the
+    /// flush.
+    pub fn finish(self) -> io::Result<()> {
+        let mut this = ManuallyDrop::new(self);
+        // Safety: `this.guard` is not used or dropped again
+        let mut guard = unsafe { ManuallyDrop::take(&mut this.guard) };
+        guard.flush()
+    }
+}
+
+impl<T: ?Sized + io::Write, Lock: crate::Lock> Deref for FlushingWriteGuard<T, Lock> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized + io::Write, Lock: crate::Lock> DerefMut for FlushingWriteGuard<T, Lock> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<T: ?Sized + io::Write, Lock: crate::Lock> io::Write for FlushingWriteGuard<T, Lock> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (**self).write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        (**self).flush()
+    }
+}
+
+impl<T: ?Sized + io::Write + fmt::Debug, Lock: crate::Lock> fmt::Debug
+    for FlushingWriteGuard<T, Lock>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FlushingWriteGuard")
+            .field("data", &&**self)
+            .finish()
+    }
+}
+
+impl<T: ?Sized + io::Write, Lock: crate::Lock> Drop for FlushingWriteGuard<T, Lock> {
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: `self.guard` is not used or dropped again. Moving it to a
+        //         local releases the lock even if `flush` panics.
+        let mut guard = unsafe { ManuallyDrop::take(&mut self.guard) };
+        let _ = guard.flush();
+    }
+}
@@ -0,0 +1,157 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::CryoMutReadGuard;
+#[cfg(feature = "std")]
+use crate::{CryoRef, SyncLock};
+#[cfg(feature = "std")]
+use core::task::Waker;
+
+/// A future that holds a [`CryoMutReadGuard`] until the inner future
+/// completes. Created by [`CryoMutReadGuard::guard_future`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct GuardedFuture<F, T: ?Sized, Lock: crate::Lock> {
+    fut: F,
+    guard: Option<CryoMutReadGuard<T, Lock>>,
+}
+
+impl<F, T: ?Sized, Lock: crate::Lock> GuardedFuture<F, T, Lock> {
+    #[inline]
+    pub(crate) fn new(fut: F, guard: CryoMutReadGuard<T, Lock>) -> Self {
+        Self {
+            fut,
+            guard: Some(guard),
+        }
+    }
+
+    /// Get the held guard. Returns `None` if the inner future has completed.
+    #[inline]
+    pub fn guard(&self) -> Option<&CryoMutReadGuard<T, Lock>> {
+        self.guard.as_ref()
+    }
+}
+
+impl<F: Future, T: ?Sized, Lock: crate::Lock> Future for GuardedFuture<F, T, Lock> {
+    type Output = F::Output;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: `fut` is structurally pinned. `guard` is not.
+        let this = unsafe { self.get_unchecked_mut() };
+        let fut = unsafe { Pin::new_unchecked(&mut this.fut) };
+        match fut.poll(cx) {
+            Poll::Ready(output) => {
+                // Release the lock as soon as possible
+                this.guard = None;
+                Poll::Ready(output)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<F: fmt::Debug, T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug
+    for GuardedFuture<F, T, Lock>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GuardedFuture")
+            .field("fut", &self.fut)
+            .field("guard", &self.guard)
+            .finish()
+    }
+}
+
+/// A [`CryoRef`]`<T, `[`SyncLock`]`>` paired with a [`Waker`] slot, for
+/// storing a frozen borrow in the state of a hand-written [`Future`].
+///
+/// The lifetime of the `Cryo`'s borrow is erased (it's enforced at runtime
+/// instead), so `CryoWaker<T>` is `'static` whenever `T: 'static`. It's
+/// [`Send`] and [`Sync`] if and only if `T: Sync`, i.e., exactly when `&T`
+/// can be shared with another thread. So a future holding it can be spawned
+/// on a multi-threaded executor as long as `T: Sync + 'static`. The `Cryo`
+/// blocks on drop until the future (and the `CryoWaker`) is dropped.
+///
+/// In the following example, `CryoWaker<u32>` is `Send + 'static` because
+/// `u32: Sync + 'static`, even though the borrowed value lives on the stack:
+///
+/// ```
+/// # use cryo::*;
+/// fn assert_send_static<T: Send + 'static>(_: &T) {}
+///
+/// with_cryo((&42u32, lock_ty::<SyncLock>()), |cryo| {
+///     let waker = CryoWaker::new(cryo.borrow());
+///     assert_send_static(&waker);
+///     assert_eq!(**waker.guard(), 42);
+/// });
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct CryoWaker<T: ?Sized> {
+    borrow: CryoRef<T, SyncLock>,
+    waker: Option<Waker>,
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> CryoWaker<T> {
+    /// Construct a `CryoWaker` with no registered `Waker`.
+    #[inline]
+    pub fn new(borrow: CryoRef<T, SyncLock>) -> Self {
+        Self {
+            borrow,
+            waker: None,
+        }
+    }
+
+    /// Get the held [`CryoRef`].
+    #[inline]
+    pub fn guard(&self) -> &CryoRef<T, SyncLock> {
+        &self.borrow
+    }
+
+    /// Register the `Waker` of the current task, replacing the previous one
+    /// unless it would wake the same task.
+    #[inline]
+    pub fn register(&mut self, waker: &Waker) {
+        match &self.waker {
+            Some(old) if old.will_wake(waker) => {}
+            _ => self.waker = Some(waker.clone()),
+        }
+    }
+
+    /// Wake the registered `Waker`, if any, and unregister it.
+    #[inline]
+    pub fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Unwrap the held [`CryoRef`], dropping the registered `Waker`.
+    #[inline]
+    pub fn into_inner(self) -> CryoRef<T, SyncLock> {
+        self.borrow
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized + fmt::Debug> fmt::Debug for CryoWaker<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CryoWaker")
+            .field("borrow", &self.borrow)
+            .field("waker", &self.waker)
+            .finish()
+    }
+}
@@ -136,7 +136,19 @@
 //!
 //! ## Feature flags
 //!
-//!  - `std` (enabled by default) enables [`SyncLock`].
+//!  - `std` (enabled by default) enables [`SyncLock`] and [`CondvarLock`].
+//!    Implies `alloc`.
+//!
+//!  - `alloc` (enabled by default through `std`) enables the features that
+//!    depend on the [`alloc`] crate, such as [`CryoMutReadGuard::to_arc`].
+//!
+//!  - `bytes` implements [`bytes::Buf`] on `CryoMutReadGuard<[u8], _>`.
+//!
+//!  - `futures` implements [`futures::Sink`] and [`futures::Stream`] on
+//!    `CryoMutWriteGuard<T, _>` if `T` does.
+//!
+//!  - `hashbrown` (with `alloc`) implements [`hashbrown::Equivalent`] so
+//!    that `CryoRef<String, _>` keys can be looked up by `&str`.
 //!
 //!  - `lock_api` enables the blanket implementation of [`Lock`] on
 //!    all types implementing [`lock_api::RawRwLock`], such as
@@ -147,6 +159,12 @@
 //!    unstable ([#32976])). This feature will be deprecated after the
 //!    stabilization of #32976.
 //!
+//!  - `atomic-block` makes [`AtomicLock`] spin-wait instead of panicking on
+//!    borrow failure. Implies `atomic`.
+//!
+//! [`futures::Sink`]: https://docs.rs/futures/0.3/futures/sink/trait.Sink.html
+//! [`futures::Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+//! [`hashbrown::Equivalent`]: https://docs.rs/hashbrown/0.15/hashbrown/trait.Equivalent.html
 //! [`spin::RawRwLock`]: https://docs.rs/spin/0.9.0/spin/type.RwLock.html
 //! [`parking_lot::RawRwLock`]: https://docs.rs/parking_lot/0.11.1/parking_lot/struct.RawRwLock.html
 //! [#32976]: https://github.com/rust-lang/rust/issues/32976
@@ -170,6 +188,7 @@ use core::{
     fmt,
     marker::{PhantomData, PhantomPinned},
     ops::{Deref, DerefMut},
+    panic::{RefUnwindSafe, UnwindSafe},
     pin::Pin,
     ptr::NonNull,
 };
@@ -179,9 +198,54 @@ use stable_deref_trait::{CloneStableDeref, StableDeref};
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 mod lock;
 pub use self::lock::*;
 
+mod cell;
+pub use self::cell::*;
+
+mod entry;
+pub use self::entry::*;
+
+mod future;
+pub use self::future::*;
+
+mod watched;
+pub use self::watched::*;
+
+mod slot;
+pub use self::slot::*;
+
+mod transparent;
+pub use self::transparent::*;
+
+mod by_address;
+pub use self::by_address::*;
+
+mod deferred;
+pub use self::deferred::*;
+
+#[cfg(feature = "std")]
+mod flushing;
+#[cfg(feature = "std")]
+pub use self::flushing::*;
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+mod shared;
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+pub use self::shared::*;
+
+pub mod prelude;
+
+#[cfg(feature = "std")]
+mod block_on;
+
+#[cfg(doctest)]
+mod compile_fail_tests;
+
 /// A cell-like type that enforces the lifetime restriction of its borrowed
 /// value at runtime.
 ///
@@ -218,7 +282,7 @@ pub struct Cryo<'a, T: ?Sized, Lock: crate::Lock> {
 unsafe impl<'a, T: ?Sized + Sync, Lock: crate::Lock> Send for Cryo<'a, T, Lock>
 where
     Lock: Send + Sync,
-    Lock::LockMarker: Send,
+    Lock: crate::lock::sealed::LockMarkerSend,
 {
 }
 
@@ -231,7 +295,7 @@ where
 unsafe impl<'a, T: ?Sized + Sync, Lock: crate::Lock> Sync for Cryo<'a, T, Lock>
 where
     Lock: Sync,
-    Lock::LockMarker: Send,
+    Lock: crate::lock::sealed::LockMarkerSend,
 {
 }
 
@@ -266,7 +330,7 @@ pub struct CryoMut<'a, T: ?Sized, Lock: crate::Lock> {
 unsafe impl<'a, T: ?Sized + Send + Sync, Lock: crate::Lock> Send for CryoMut<'a, T, Lock>
 where
     Lock: Send + Sync,
-    Lock::LockMarker: Send,
+    Lock: crate::lock::sealed::LockMarkerSend,
 {
 }
 
@@ -278,7 +342,7 @@ where
 unsafe impl<'a, T: ?Sized + Send + Sync, Lock: crate::Lock> Sync for CryoMut<'a, T, Lock>
 where
     Lock: Sync,
-    Lock::LockMarker: Send,
+    Lock: crate::lock::sealed::LockMarkerSend,
 {
 }
 
@@ -288,13 +352,132 @@ struct State<T: ?Sized, Lock> {
     _phantom: PhantomPinned,
 }
 
+impl<T: ?Sized, Lock: crate::Lock> State<T, Lock> {
+    /// Construct a [`CryoMutReadGuard`] for a shared lock held by the caller.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own a shared lock on `self.lock`, which will be
+    /// transferred to the returned guard. `self` must be pinned.
+    #[inline]
+    unsafe fn read_guard(&self) -> CryoMutReadGuard<T, Lock> {
+        CryoMutReadGuard {
+            data: self.data,
+            lock: NonNull::from(&self.lock),
+        }
+    }
+
+    /// Construct a [`CryoMutWriteGuard`] for an exclusive lock held by the
+    /// caller.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own an exclusive lock on `self.lock`, which will be
+    /// transferred to the returned guard. `self` must be pinned.
+    #[inline]
+    unsafe fn write_guard(&self) -> CryoMutWriteGuard<T, Lock> {
+        CryoMutWriteGuard {
+            data: self.data,
+            lock: NonNull::from(&self.lock),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Check if there are outstanding guards.
+    ///
+    /// # Safety
+    ///
+    /// If `Lock::LockMarker` is `!`[`Send`], the current thread must be the
+    /// same one as `self.lock`'s creator.
+    #[inline]
+    unsafe fn is_borrowed(&self) -> bool {
+        if self.lock.try_lock_exclusive() {
+            self.lock.unlock_exclusive();
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Panic if there are outstanding guards.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::is_borrowed`].
+    #[inline]
+    #[track_caller]
+    unsafe fn assert_not_borrowed(&self, type_name: &str) {
+        if self.is_borrowed() {
+            panic!("`{}` is still borrowed", type_name);
+        }
+    }
+
+    /// Acquire an exclusive lock to ensure there are no outstanding guards
+    /// before the cell is destroyed. See [`seal_lock`].
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::is_borrowed`].
+    #[inline]
+    unsafe fn seal(&self, type_name: &str) {
+        seal_lock(&self.lock, type_name);
+    }
+}
+
+/// Acquire an exclusive lock on `lock` to ensure there are no outstanding
+/// guards before a cell of type `type_name` is destroyed.
+///
+/// If `Lock` panics on contention, debug builds check the number of
+/// outstanding shared locks first (if `Lock` tracks it) and panic with a
+/// descriptive message instead of the lock's own.
+///
+/// # Safety
+///
+/// The current thread must be allowed to lock `lock` (see
+/// [`Lock::LockMarker`]).
+#[inline]
+pub(crate) unsafe fn seal_lock<Lock: crate::Lock>(lock: &Lock, type_name: &str) {
+    if cfg!(debug_assertions) && Lock::ON_CONTENTION == Contention::Panic {
+        match lock.num_shared_locks() {
+            Some(n) if n > 0 => seal_fail(type_name, n),
+            _ => {}
+        }
+    }
+    lock.lock_exclusive();
+}
+
+#[cold]
+fn seal_fail(type_name: &str, num_shared_locks: usize) -> ! {
+    panic!(
+        "`{}` was dropped while {} read guard(s) were outstanding",
+        type_name, num_shared_locks
+    )
+}
+
 /// The lock guard type of [`Cryo`]. This is currently a type alias but might
 /// change in a future.
 pub type CryoRef<T, Lock> = CryoMutReadGuard<T, Lock>;
 
 /// The read lock guard type of [`CryoMut`].
+///
+/// `CryoMutReadGuard` implements [`Clone`] regardless of whether `T` or `Lock`
+/// does. Note that `#[derive(Clone)]` on a struct generic over `T` or `Lock`
+/// would still add `T: Clone` and `Lock: Clone` bounds; implement `Clone`
+/// manually to avoid them:
+///
+/// ```
+/// # use cryo::*;
+/// struct Holder<T: ?Sized, L: Lock>(CryoRef<T, L>);
+///
+/// impl<T: ?Sized, L: Lock> Clone for Holder<T, L> {
+///     fn clone(&self) -> Self {
+///         Self(self.0.clone())
+///     }
+/// }
+/// ```
 pub struct CryoMutReadGuard<T: ?Sized, Lock: crate::Lock> {
-    state: NonNull<State<T, Lock>>,
+    data: NonNull<T>,
+    lock: NonNull<Lock>,
 }
 
 /// `CryoMutReadGuard` is essentially `&T` with an indeterminate lifetime.
@@ -302,7 +485,7 @@ pub struct CryoMutReadGuard<T: ?Sized, Lock: crate::Lock> {
 unsafe impl<T: ?Sized + Sync, Lock: crate::Lock> Send for CryoMutReadGuard<T, Lock>
 where
     Lock: Sync,
-    Lock::UnlockMarker: Send,
+    Lock: crate::lock::sealed::UnlockMarkerSend,
 {
 }
 
@@ -312,9 +495,23 @@ where
 /// bounds is not constrained by `Lock`.
 unsafe impl<T: ?Sized + Sync, Lock: crate::Lock> Sync for CryoMutReadGuard<T, Lock> {}
 
+/// `CryoMutReadGuard` is essentially `&T`. The lock state stays consistent
+/// across unwinding, so `Lock` doesn't matter here.
+impl<T: ?Sized + RefUnwindSafe, Lock: crate::Lock> UnwindSafe for CryoMutReadGuard<T, Lock> {}
+impl<T: ?Sized + RefUnwindSafe, Lock: crate::Lock> RefUnwindSafe for CryoMutReadGuard<T, Lock> {}
+
 /// The write lock guard type of [`CryoMut`].
 pub struct CryoMutWriteGuard<T: ?Sized, Lock: crate::Lock> {
-    state: NonNull<State<T, Lock>>,
+    data: NonNull<T>,
+    lock: NonNull<Lock>,
+    /// Makes `CryoMutWriteGuard` invariant over `T` like `&mut T`.
+    /// `NonNull<T>` alone is covariant, which would allow writing a
+    /// short-lived value through a guard of a long-lived type.
+    ///
+    /// `&mut ()` makes it `!UnwindSafe` like `&mut T`. A panic in the middle
+    /// of a write may leave the referent in an inconsistent state, and
+    /// there's no poisoning to report it.
+    _phantom: PhantomData<(*mut T, &'static mut ())>,
 }
 
 /// `CryoMutWriteGuard` is essentially `&mut T` with an indeterminate lifetime.
@@ -322,7 +519,7 @@ pub struct CryoMutWriteGuard<T: ?Sized, Lock: crate::Lock> {
 unsafe impl<T: ?Sized + Send, Lock: crate::Lock> Send for CryoMutWriteGuard<T, Lock>
 where
     Lock: Sync,
-    Lock::UnlockMarker: Send,
+    Lock: crate::lock::sealed::UnlockMarkerSend,
 {
 }
 
@@ -332,6 +529,9 @@ where
 /// bounds is not constrained by `Lock`.
 unsafe impl<T: ?Sized + Sync, Lock: crate::Lock> Sync for CryoMutWriteGuard<T, Lock> {}
 
+/// `&CryoMutWriteGuard` only grants `&T`.
+impl<T: ?Sized + RefUnwindSafe, Lock: crate::Lock> RefUnwindSafe for CryoMutWriteGuard<T, Lock> {}
+
 impl<'a, T: ?Sized + 'a, Lock: crate::Lock> Cryo<'a, T, Lock> {
     /// Construct a new `Cryo`.
     ///
@@ -386,18 +586,78 @@ impl<'a, T: ?Sized + 'a, Lock: crate::Lock> Cryo<'a, T, Lock> {
     pub fn borrow(self: Pin<&Self>) -> CryoRef<T, Lock> {
         // Safety: `&Cryo`'s `Send`-ness is constrained by that of `Lock::LockMarker`
         unsafe { self.state.lock.lock_shared() };
-        CryoRef {
-            state: NonNull::from(&self.state),
+        unsafe { self.state.read_guard() }
+    }
+
+    /// Attempt to borrow a cell using runtime lifetime rules.
+    ///
+    /// Returns `None` if the lock's internal counter would overflow.
+    #[inline]
+    pub fn try_borrow(self: Pin<&Self>) -> Option<CryoRef<T, Lock>> {
+        // Safety: `&Cryo`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        if unsafe { self.state.lock.try_lock_shared() } {
+            Some(unsafe { self.state.read_guard() })
+        } else {
+            None
         }
     }
 
+    /// Panic if there are outstanding [`CryoRef`]s.
+    ///
+    /// This is useful in tests to catch leaked borrows early and at a
+    /// specific point, rather than when the `Cryo` is dropped.
+    ///
+    /// ```should_panic
+    /// # use cryo::*;
+    /// with_cryo(&42, |cryo| {
+    ///     cryo.assert_not_borrowed();
+    ///     let _borrow = cryo.borrow();
+    ///     cryo.assert_not_borrowed(); // panics
+    /// });
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn assert_not_borrowed(self: Pin<&Self>) {
+        // Safety: `&Cryo`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { self.state.assert_not_borrowed("Cryo") };
+    }
+
+    /// Check if dropping the `Cryo` right now would block (or panic, depending
+    /// on [`Lock::ON_CONTENTION`]) because there are outstanding
+    /// [`CryoRef`]s.
+    ///
+    /// This is a best-effort check for diagnostics. The result may be
+    /// outdated by the time it's returned if guards are dropped on other
+    /// threads.
+    #[inline]
+    pub fn would_block_on_seal(self: Pin<&Self>) -> bool {
+        // Safety: `&Cryo`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { self.state.is_borrowed() }
+    }
+
     /// Borrow a cell using compile-time lifetime rules.
     ///
     /// This operation is no-op since `Cryo` only can be immutably borrowed.
+    /// The returned reference carries the original lifetime `'a` and thus may
+    /// outlive `self`:
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// let cell = (1, 2);
+    /// let second: &i32 = with_cryo(&cell, |cryo| &cryo.get().1);
+    /// assert_eq!(*second, 2);
+    /// ```
     #[inline]
     pub fn get(&self) -> &'a T {
         unsafe { &*self.state.data.as_ptr() }
     }
+
+    /// Get a reference to the underlying [`Lock`], e.g., to read the counters
+    /// of an [`InstrumentedLock`].
+    #[inline]
+    pub fn raw_lock(&self) -> &Lock {
+        &self.state.lock
+    }
 }
 
 impl<'a, T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for Cryo<'a, T, Lock> {
@@ -406,11 +666,51 @@ impl<'a, T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for Cryo<'a, T, L
     }
 }
 
+/// Compares the referents.
+impl<'a, T: ?Sized + PartialEq, Lock: crate::Lock> PartialEq for Cryo<'a, T, Lock> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<'a, T: ?Sized + Eq, Lock: crate::Lock> Eq for Cryo<'a, T, Lock> {}
+
+/// Compares the referents.
+impl<'a, T: ?Sized + PartialOrd, Lock: crate::Lock> PartialOrd for Cryo<'a, T, Lock> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.get().partial_cmp(other.get())
+    }
+}
+
+/// Compares the referents.
+///
+/// ```
+/// # use cryo::*;
+/// let cells = [3, 1, 2];
+/// // Safety: `cryos` is dropped before `cells`
+/// let mut cryos: Vec<_> = cells
+///     .iter()
+///     .map(|x| Box::pin(unsafe { Cryo::<_, LocalLock>::new(x) }))
+///     .collect();
+/// // `Pin<Box<Cryo>>` compares the `Cryo`s
+/// cryos.sort();
+/// let sorted: Vec<u32> = cryos.iter().map(|c| *c.as_ref().borrow()).collect();
+/// assert_eq!(sorted, [1, 2, 3]);
+/// ```
+impl<'a, T: ?Sized + Ord, Lock: crate::Lock> Ord for Cryo<'a, T, Lock> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.get().cmp(other.get())
+    }
+}
+
 impl<'a, T: ?Sized + 'a, Lock: crate::Lock> Drop for Cryo<'a, T, Lock> {
     #[inline]
     fn drop(&mut self) {
         // Safety: `&Cryo`'s `Send`-ness is constrained by that of `Lock::LockMarker`
-        unsafe { self.state.lock.lock_exclusive() };
+        unsafe { self.state.seal("Cryo") };
         // A write lock ensures there are no other references to
         // the contents
     }
@@ -443,9 +743,7 @@ impl<'a, T: ?Sized + 'a, Lock: crate::Lock> CryoMut<'a, T, Lock> {
     pub fn read(self: Pin<&Self>) -> CryoMutReadGuard<T, Lock> {
         // Safety: `&CryoMut`'s `Send`-ness is constrained by that of `Lock::LockMarker`
         unsafe { self.state.lock.lock_shared() };
-        CryoMutReadGuard {
-            state: NonNull::from(&self.state),
-        }
+        unsafe { self.state.read_guard() }
     }
 
     /// Attempt to acquire a read (shared) lock on a `CryoMut`.
@@ -453,9 +751,7 @@ impl<'a, T: ?Sized + 'a, Lock: crate::Lock> CryoMut<'a, T, Lock> {
     pub fn try_read(self: Pin<&Self>) -> Option<CryoMutReadGuard<T, Lock>> {
         // Safety: `&CryoMut`'s `Send`-ness is constrained by that of `Lock::LockMarker`
         if unsafe { self.state.lock.try_lock_shared() } {
-            Some(CryoMutReadGuard {
-                state: NonNull::from(&self.state),
-            })
+            Some(unsafe { self.state.read_guard() })
         } else {
             None
         }
@@ -466,9 +762,7 @@ impl<'a, T: ?Sized + 'a, Lock: crate::Lock> CryoMut<'a, T, Lock> {
     pub fn write(self: Pin<&Self>) -> CryoMutWriteGuard<T, Lock> {
         // Safety: `&CryoMut`'s `Send`-ness is constrained by that of `Lock::LockMarker`
         unsafe { self.state.lock.lock_exclusive() };
-        CryoMutWriteGuard {
-            state: NonNull::from(&self.state),
-        }
+        unsafe { self.state.write_guard() }
     }
 
     /// Attempt to acquire a write (exclusive) lock on a `CryoMut`.
@@ -476,34 +770,377 @@ impl<'a, T: ?Sized + 'a, Lock: crate::Lock> CryoMut<'a, T, Lock> {
     pub fn try_write(self: Pin<&Self>) -> Option<CryoMutWriteGuard<T, Lock>> {
         // Safety: `&CryoMut`'s `Send`-ness is constrained by that of `Lock::LockMarker`
         if unsafe { self.state.lock.try_lock_exclusive() } {
-            Some(CryoMutWriteGuard {
-                state: NonNull::from(&self.state),
-            })
+            Some(unsafe { self.state.write_guard() })
+        } else {
+            None
+        }
+    }
+
+    /// Panic if there are outstanding [`CryoMutReadGuard`]s or
+    /// [`CryoMutWriteGuard`]s.
+    ///
+    /// This is useful in tests to catch leaked borrows early and at a
+    /// specific point, rather than when the `CryoMut` is dropped.
+    #[inline]
+    #[track_caller]
+    pub fn assert_not_borrowed(self: Pin<&Self>) {
+        // Safety: `&CryoMut`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { self.state.assert_not_borrowed("CryoMut") };
+    }
+
+    /// Check if dropping the `CryoMut` right now would block (or panic,
+    /// depending on [`Lock::ON_CONTENTION`]) because there are outstanding
+    /// [`CryoMutReadGuard`]s or [`CryoMutWriteGuard`]s.
+    ///
+    /// This is a best-effort check for diagnostics. The result may be
+    /// outdated by the time it's returned if guards are dropped on other
+    /// threads.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&mut 42, |cryo_mut| {
+    ///     assert!(!cryo_mut.would_block_on_seal());
+    ///     let borrow = cryo_mut.read();
+    ///     assert!(cryo_mut.would_block_on_seal());
+    /// });
+    /// ```
+    #[inline]
+    pub fn would_block_on_seal(self: Pin<&Self>) -> bool {
+        // Safety: `&CryoMut`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { self.state.is_borrowed() }
+    }
+
+    /// Acquire a read (shared) lock on a `CryoMut`, call `f` with the
+    /// referent, and return the guard along with `f`'s result.
+    ///
+    /// Unlike [`Self::with_field_mut`], the lock is not released before this
+    /// method returns.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&mut vec![3, 1, 2], |cryo_mut| {
+    ///     let (guard, max_i) = cryo_mut.read_then(|v| (0..v.len()).max_by_key(|&i| v[i]));
+    ///     assert_eq!(guard[max_i.unwrap()], 3);
+    /// });
+    /// ```
+    #[inline]
+    pub fn read_then<R>(
+        self: Pin<&Self>,
+        f: impl FnOnce(&T) -> R,
+    ) -> (CryoMutReadGuard<T, Lock>, R) {
+        let guard = self.read();
+        let output = f(&guard);
+        (guard, output)
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut`, call `f` with the
+    /// referent, and return the guard along with `f`'s result.
+    ///
+    /// Unlike [`Self::with_field_mut`], the lock is not released before this
+    /// method returns.
+    #[inline]
+    pub fn write_then<R>(
+        self: Pin<&Self>,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> (CryoMutWriteGuard<T, Lock>, R) {
+        let mut guard = self.write();
+        let output = f(&mut guard);
+        (guard, output)
+    }
+
+    /// Attempt to acquire a write (exclusive) lock on a `CryoMut`, retrying up
+    /// to `max_spins` times if the `Lock` implementation supports it (see
+    /// [`Lock::try_lock_exclusive_spin`]).
+    #[inline]
+    pub fn try_write_spins(self: Pin<&Self>, max_spins: u32) -> Option<CryoMutWriteGuard<T, Lock>> {
+        // Safety: `&CryoMut`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        if unsafe { self.state.lock.try_lock_exclusive_spin(max_spins) } {
+            Some(unsafe { self.state.write_guard() })
+        } else {
+            None
+        }
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut` and replace the
+    /// referent with `value`. The old value is dropped while the lock is
+    /// held.
+    #[inline]
+    pub fn set(self: Pin<&Self>, value: T)
+    where
+        T: Sized,
+    {
+        *self.write() = value;
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut`, replace the referent
+    /// with `value`, and return the old value.
+    #[inline]
+    pub fn replace(self: Pin<&Self>, value: T) -> T
+    where
+        T: Sized,
+    {
+        core::mem::replace(&mut *self.write(), value)
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut`, replace the referent
+    /// with the value returned by `f`, and return the old value.
+    ///
+    /// If `f` panics, the referent is left as `f` left it, and the lock is
+    /// released.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&mut 1, |cryo_mut| {
+    ///     assert_eq!(cryo_mut.replace_with(|x| *x + 1), 1);
+    ///     assert_eq!(*cryo_mut.read(), 2);
+    /// });
+    /// ```
+    #[inline]
+    pub fn replace_with(self: Pin<&Self>, f: impl FnOnce(&mut T) -> T) -> T
+    where
+        T: Sized,
+    {
+        let mut guard = self.write();
+        let value = f(&mut guard);
+        core::mem::replace(&mut *guard, value)
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut`, and take the referent,
+    /// leaving `T::default()` in its place.
+    #[inline]
+    pub fn take(self: Pin<&Self>) -> T
+    where
+        T: Default,
+    {
+        core::mem::take(&mut *self.write())
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut`, and if the referent
+    /// is equal to `current`, replace it with `new`.
+    ///
+    /// Returns `Ok(old)` with the replaced value on success, and `Err(new)`
+    /// handing back the unused value otherwise.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&mut 1, |cryo_mut| {
+    ///     assert_eq!(cryo_mut.compare_exchange(&1, 2), Ok(1));
+    ///     assert_eq!(cryo_mut.compare_exchange(&1, 3), Err(3));
+    ///     assert_eq!(*cryo_mut.read(), 2);
+    /// });
+    /// ```
+    #[inline]
+    pub fn compare_exchange(self: Pin<&Self>, current: &T, new: T) -> Result<T, T>
+    where
+        T: PartialEq + Sized,
+    {
+        let mut guard = self.write();
+        if *guard == *current {
+            Ok(core::mem::replace(&mut *guard, new))
+        } else {
+            Err(new)
+        }
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut`, and call `f` with the
+    /// referent. If `f` returns `Some(new)`, replace the referent with `new`.
+    ///
+    /// Returns `Ok(old)` with the replaced value if `f` returned `Some(_)`,
+    /// and `Err(current)` with a clone of the unchanged referent otherwise.
+    /// This mirrors [`AtomicUsize::fetch_update`], except that `f` is called
+    /// only once because the lock excludes concurrent updates.
+    ///
+    /// [`AtomicUsize::fetch_update`]: core::sync::atomic::AtomicUsize::fetch_update
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&mut 1u32, |cryo_mut| {
+    ///     assert_eq!(cryo_mut.fetch_update(|x| x.checked_sub(1)), Ok(1));
+    ///     assert_eq!(cryo_mut.fetch_update(|x| x.checked_sub(1)), Err(0));
+    /// });
+    /// ```
+    #[inline]
+    pub fn fetch_update(self: Pin<&Self>, f: impl FnOnce(&T) -> Option<T>) -> Result<T, T>
+    where
+        T: Clone,
+    {
+        let mut guard = self.write();
+        match f(&guard) {
+            Some(new) => Ok(core::mem::replace(&mut *guard, new)),
+            None => Err(T::clone(&guard)),
+        }
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut`, call `f` with the
+    /// referent, and release the lock.
+    #[inline]
+    pub fn update<R>(self: Pin<&Self>, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.write())
+    }
+
+    /// Attempt to acquire a write (exclusive) lock on a `CryoMut` and replace
+    /// the referent with `value`.
+    ///
+    /// Unlike [`Self::set`], this doesn't block or panic if the `CryoMut` is
+    /// already borrowed. `value` is handed back as `Err(value)` instead.
+    #[inline]
+    pub fn try_set(self: Pin<&Self>, value: T) -> Result<(), T>
+    where
+        T: Sized,
+    {
+        match self.try_write() {
+            Some(mut guard) => {
+                *guard = value;
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+
+    /// Attempt to acquire a write (exclusive) lock on a `CryoMut`, replace the
+    /// referent with `value`, and return the old value.
+    ///
+    /// Unlike [`Self::replace`], this doesn't block or panic if the `CryoMut`
+    /// is already borrowed. `value` is handed back as `Err(value)` instead.
+    #[inline]
+    pub fn try_replace(self: Pin<&Self>, value: T) -> Result<T, T>
+    where
+        T: Sized,
+    {
+        match self.try_write() {
+            Some(mut guard) => Ok(core::mem::replace(&mut *guard, value)),
+            None => Err(value),
+        }
+    }
+
+    /// Attempt to acquire a write (exclusive) lock on a `CryoMut`, call `f`
+    /// with the referent, and release the lock.
+    ///
+    /// Unlike [`Self::update`], this doesn't block or panic if the `CryoMut`
+    /// is already borrowed. `None` is returned instead, and `f` is not called.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&mut 1, |cryo_mut| {
+    ///     let borrow = cryo_mut.read();
+    ///     assert_eq!(cryo_mut.try_update(|x| *x += 1), None);
+    ///     drop(borrow);
+    ///     assert_eq!(cryo_mut.try_update(|x| *x += 1), Some(()));
+    ///     assert_eq!(*cryo_mut.read(), 2);
+    /// });
+    /// ```
+    #[inline]
+    pub fn try_update<R>(self: Pin<&Self>, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.try_write().map(|mut guard| f(&mut guard))
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut`, call `accessor` to
+    /// project the referent to a field, and call `f` with the field.
+    ///
+    /// The lock is released before this method returns.
+    #[inline]
+    pub fn with_field_mut<F: ?Sized, R>(
+        self: Pin<&Self>,
+        accessor: impl FnOnce(&mut T) -> &mut F,
+        f: impl FnOnce(&mut F) -> R,
+    ) -> R {
+        let mut guard = self.write();
+        f(accessor(&mut guard))
+    }
+
+    /// Acquire a write (exclusive) lock on a `CryoMut`, call `read_phase`
+    /// with the referent, and if it returns `true`, call `write_phase` with
+    /// the referent.
+    ///
+    /// Returns `None` if `read_phase` returned `false`. The lock is held
+    /// continuously from `read_phase` through `write_phase` and is released
+    /// before this method returns.
+    ///
+    /// [`Lock`] doesn't support upgrading a shared lock, so both phases run
+    /// under an exclusive lock.
+    #[inline]
+    pub fn transact<R>(
+        self: Pin<&Self>,
+        read_phase: impl FnOnce(&T) -> bool,
+        write_phase: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        let mut guard = self.write();
+        if read_phase(&guard) {
+            Some(write_phase(&mut guard))
         } else {
             None
         }
     }
 
+    /// Acquire a write (exclusive) lock on a `CryoMut`, get the entry for
+    /// `key` in the referent, and call `f` with the entry.
+    ///
+    /// The lock is released before this method returns.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map = HashMap::new();
+    /// with_cryo(&mut map, |cryo_mut| {
+    ///     cryo_mut.with_entry("a", |e| *e.or_insert(0) += 1);
+    /// });
+    /// assert_eq!(map["a"], 1);
+    /// ```
+    #[inline]
+    pub fn with_entry<K, R>(
+        self: Pin<&Self>,
+        key: K,
+        f: impl for<'e> FnOnce(<T as EntryMap<'e, K>>::Entry) -> R,
+    ) -> R
+    where
+        T: for<'e> EntryMap<'e, K>,
+    {
+        let mut guard = self.write();
+        f(EntryMap::entry(&mut *guard, key))
+    }
+
     /// Attempt to mutably borrow a `CryoMut` using compile-time lifetime rules.
     ///
     /// Returns `None` if the `CryoMut` is already borrowed via
     /// [`CryoMutReadGuard`] or [`CryoMutWriteGuard`].
     #[inline]
-    pub fn try_get_mut<'this>(self: Pin<&'this mut Self>) -> Option<&'this mut T> {
-        // FIXME: The lifetime elision is not possible here because of
-        //        <https://github.com/rust-lang/rust/issues/52675>
+    pub fn try_get_mut(self: Pin<&mut Self>) -> Option<&mut T> {
         if self.as_ref().try_write().is_some() {
             Some(unsafe { &mut *self.state.data.as_ptr() })
         } else {
             None
         }
     }
+
+    /// Get a reference to the underlying [`Lock`], e.g., to read the counters
+    /// of an [`InstrumentedLock`].
+    #[inline]
+    pub fn raw_lock(&self) -> &Lock {
+        &self.state.lock
+    }
+
+    /// Get a snapshot of the lock's state by [`Lock::contention_snapshot`].
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo((&mut 42, lock_ty::<SyncLock>()), |cryo_mut| {
+    ///     let _guard = cryo_mut.read();
+    ///     let state = cryo_mut.lock_state().unwrap();
+    ///     assert_eq!(state.num_shared_locks, 1);
+    ///     assert!(!state.exclusive);
+    /// });
+    /// ```
+    #[inline]
+    pub fn lock_state(self: Pin<&Self>) -> Option<LockState> {
+        self.state.lock.contention_snapshot()
+    }
 }
 
 impl<'a, T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for CryoMut<'a, T, Lock> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Safety: The constructed `CryoMutReadGuard` doesn't outlive `self`, so
-        //         `CryoMutReadGuard::state` won't get dangling.
+        //         `CryoMutReadGuard::{data, lock}` won't get dangling.
         let this = unsafe { Pin::new_unchecked(self) };
         if let Some(x) = this.try_read() {
             f.debug_struct("CryoMut").field("data", &&*x).finish()
@@ -521,42 +1158,305 @@ impl<'a, T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for CryoMut<'a, T
     }
 }
 
-impl<'a, T: ?Sized + 'a, Lock: crate::Lock> Drop for CryoMut<'a, T, Lock> {
+impl<'a, T: ?Sized, Lock: crate::Lock> CryoMut<'a, T, Lock> {
+    /// Call `f` with the referent, or with `None` if it's locked exclusively.
     #[inline]
-    fn drop(&mut self) {
-        // Safety: `&CryoMut`'s `Send`-ness is constrained by that of `Lock::LockMarker`
-        unsafe { self.state.lock.lock_exclusive() };
-        // A write lock ensures there are no other references to
-        // the contents
+    fn with_try_read<R>(&self, f: impl FnOnce(Option<&T>) -> R) -> R {
+        // Safety: The constructed `CryoMutReadGuard` doesn't outlive `self`, so
+        //         `CryoMutReadGuard::{data, lock}` won't get dangling.
+        let this = unsafe { Pin::new_unchecked(self) };
+        f(this.try_read().as_deref())
     }
 }
 
-impl<T: ?Sized, Lock: crate::Lock> CryoMutReadGuard<T, Lock> {
+/// Compares the referents. A `CryoMut` that is locked exclusively (so its
+/// referent can't be read) is considered greater than any other `CryoMut`
+/// and equal to another locked one.
+impl<'a, T: ?Sized + PartialEq, Lock: crate::Lock> PartialEq for CryoMut<'a, T, Lock> {
     #[inline]
-    unsafe fn state(&self) -> &State<T, Lock> {
-        self.state.as_ref()
+    fn eq(&self, other: &Self) -> bool {
+        self.with_try_read(|x| other.with_try_read(|y| x == y))
     }
 }
 
-impl<T: ?Sized, Lock: crate::Lock> Deref for CryoMutReadGuard<T, Lock> {
-    type Target = T;
+impl<'a, T: ?Sized + Eq, Lock: crate::Lock> Eq for CryoMut<'a, T, Lock> {}
 
+/// Compares the referents. A `CryoMut` that is locked exclusively is
+/// considered greater than any other `CryoMut`.
+impl<'a, T: ?Sized + PartialOrd, Lock: crate::Lock> PartialOrd for CryoMut<'a, T, Lock> {
     #[inline]
-    fn deref(&self) -> &Self::Target {
-        unsafe { self.state().data.as_ref() }
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        use core::cmp::Ordering;
+        self.with_try_read(|x| {
+            other.with_try_read(|y| match (x, y) {
+                (Some(x), Some(y)) => x.partial_cmp(y),
+                (Some(_), None) => Some(Ordering::Less),
+                (None, Some(_)) => Some(Ordering::Greater),
+                (None, None) => Some(Ordering::Equal),
+            })
+        })
     }
 }
 
-unsafe impl<T: ?Sized, Lock: crate::Lock> StableDeref for CryoMutReadGuard<T, Lock> {}
-unsafe impl<T: ?Sized, Lock: crate::Lock> CloneStableDeref for CryoMutReadGuard<T, Lock> {}
+/// Compares the referents. A `CryoMut` that is locked exclusively is
+/// considered greater than any other `CryoMut`.
+impl<'a, T: ?Sized + Ord, Lock: crate::Lock> Ord for CryoMut<'a, T, Lock> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        use core::cmp::Ordering;
+        self.with_try_read(|x| {
+            other.with_try_read(|y| match (x, y) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+        })
+    }
+}
+
+impl<'a, T: ?Sized + 'a, Lock: crate::Lock> Drop for CryoMut<'a, T, Lock> {
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: `&CryoMut`'s `Send`-ness is constrained by that of `Lock::LockMarker`
+        unsafe { self.state.seal("CryoMut") };
+        // A write lock ensures there are no other references to
+        // the contents
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> CryoMutReadGuard<T, Lock> {
+    #[inline]
+    unsafe fn lock(&self) -> &Lock {
+        self.lock.as_ref()
+    }
+
+    /// Replace the data pointer, transferring the ownership of the lock to the
+    /// returned guard.
+    ///
+    /// # Safety
+    ///
+    /// `data` must remain valid and immutable while the shared lock is held.
+    #[inline]
+    unsafe fn map_data<U: ?Sized>(self, data: NonNull<U>) -> CryoMutReadGuard<U, Lock> {
+        let lock = self.lock;
+        core::mem::forget(self);
+        CryoMutReadGuard { data, lock }
+    }
+
+    /// Make a new `CryoMutReadGuard` for a component of the referent, keeping
+    /// the shared lock.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `CryoMutReadGuard::map(...)` so that it doesn't shadow the referent's
+    /// methods, such as [`Option::map`].
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&(1, 2), |cryo| {
+    ///     let second: CryoRef<i32, _> = CryoRef::map(cryo.borrow(), |x| &x.1);
+    ///     assert_eq!(*second, 2);
+    /// });
+    /// ```
+    #[inline]
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&T) -> &U) -> CryoMutReadGuard<U, Lock> {
+        let data = NonNull::from(f(&this));
+        // Safety: The component is borrowed from the referent
+        unsafe { this.map_data(data) }
+    }
+
+    /// Attempt to make a new `CryoMutReadGuard` for a component of the
+    /// referent, keeping the shared lock. If `f` returns `Err(e)`, the
+    /// original guard is returned along with `e`.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `CryoMutReadGuard::try_map(...)`. See [`Self::map`].
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&[1, 2, 3][..], |cryo| {
+    ///     let (borrow, e) = CryoRef::try_map(cryo.borrow(), |x| x.get(3).ok_or("oob")).unwrap_err();
+    ///     assert_eq!(e, "oob");
+    ///     let third = CryoRef::try_map(borrow, |x| x.get(2).ok_or("oob")).unwrap();
+    ///     assert_eq!(*third, 3);
+    /// });
+    /// ```
+    #[inline]
+    pub fn try_map<U: ?Sized, E>(
+        this: Self,
+        f: impl FnOnce(&T) -> Result<&U, E>,
+    ) -> Result<CryoMutReadGuard<U, Lock>, (Self, E)> {
+        match f(&this) {
+            Ok(x) => {
+                let data = NonNull::from(x);
+                // Safety: The component is borrowed from the referent
+                Ok(unsafe { this.map_data(data) })
+            }
+            Err(e) => Err((this, e)),
+        }
+    }
+
+    /// Return `Some(this)` if `pred` returns `true` for the referent.
+    /// Otherwise, drop `this`, releasing the shared lock, and return `None`.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `CryoMutReadGuard::filter(...)`. See [`Self::map`].
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&42, |cryo| {
+    ///     assert!(cryo.try_borrow().and_then(|b| CryoRef::filter(b, |x| *x > 50)).is_none());
+    ///     assert!(!cryo.would_block_on_seal());
+    /// });
+    /// ```
+    #[inline]
+    pub fn filter(this: Self, pred: impl FnOnce(&T) -> bool) -> Option<Self> {
+        if pred(&this) {
+            Some(this)
+        } else {
+            None
+        }
+    }
+
+    /// Convert `CryoMutReadGuard<T, _>` into a guard pointing to `T`'s
+    /// [`Deref`] target, e.g., `CryoRef<String, _>` into `CryoRef<str, _>`.
+    ///
+    /// `T` doesn't need to implement [`StableDeref`]: the referent can't be
+    /// moved or mutated while the shared lock is held, so the reference
+    /// returned by [`Deref::deref`] remains valid until the guard is dropped.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&vec![1, 2, 3], |cryo| {
+    ///     let borrow: CryoRef<[i32], _> = cryo.borrow().deref_into();
+    ///     assert_eq!(*borrow, [1, 2, 3]);
+    /// });
+    /// ```
+    #[inline]
+    pub fn deref_into(self) -> CryoMutReadGuard<T::Target, Lock>
+    where
+        T: Deref,
+    {
+        let data = NonNull::from(&**self);
+        // Safety: The target is borrowed from the referent
+        unsafe { self.map_data(data) }
+    }
+
+    /// Get a raw pointer to the referent.
+    ///
+    /// The pointer is valid for reads as long as `this` or any of its clones
+    /// is alive. It's the same for all clones of `this`.
+    ///
+    /// This is an associated function so that it doesn't shadow the
+    /// referent's methods, such as [`str::as_ptr`].
+    #[inline]
+    pub fn as_ptr(this: &Self) -> *const T {
+        this.data.as_ptr()
+    }
+
+    /// Get a [`NonNull`] pointer to the referent.
+    ///
+    /// The pointer is valid for reads as long as `this` or any of its clones
+    /// is alive. It must not be used for writes.
+    #[inline]
+    pub fn as_non_null(this: &Self) -> NonNull<T> {
+        this.data
+    }
+
+    /// Wrap a future so that `self` is dropped as soon as the future
+    /// completes, rather than when the wrapping future is dropped.
+    #[inline]
+    pub fn guard_future<F: core::future::Future>(self, fut: F) -> GuardedFuture<F, T, Lock> {
+        GuardedFuture::new(fut, self)
+    }
+
+    /// Clone the referent into a new [`Arc`](alloc::sync::Arc).
+    ///
+    /// The returned `Arc` owns a copy of the referent and doesn't share
+    /// storage with the frozen referent. Thus, unlike `CryoMutReadGuard`, it
+    /// doesn't prevent the `Cryo` or `CryoMut` from being dropped.
+    #[cfg(all(feature = "alloc", feature = "atomic"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", feature = "atomic"))))]
+    #[inline]
+    pub fn to_arc(&self) -> alloc::sync::Arc<T>
+    where
+        T: Clone,
+    {
+        alloc::sync::Arc::new((**self).clone())
+    }
+
+    /// Convert `self` into a [`SharedCryoRef`], which is cloned by
+    /// incrementing an [`Arc`](alloc::sync::Arc)'s reference count and can be
+    /// downgraded to a [`WeakCryoRef`].
+    #[cfg(all(feature = "alloc", feature = "atomic"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", feature = "atomic"))))]
+    #[inline]
+    pub fn into_shared(self) -> SharedCryoRef<T, Lock> {
+        SharedCryoRef::new(self)
+    }
+
+    /// Wrap `self` with [`ByAddress`] to hash and compare it by which cell it
+    /// was borrowed from, rather than by value.
+    #[inline]
+    pub fn by_address(self) -> ByAddress<Self> {
+        ByAddress(self)
+    }
+
+    /// Wrap `self` with [`DeferredRelease`] to call `on_release` right after
+    /// the shared lock is released.
+    #[inline]
+    pub fn defer_release<F: FnOnce()>(self, on_release: F) -> DeferredRelease<T, Lock, F> {
+        DeferredRelease::new(self, on_release)
+    }
+
+    /// Clone the referent into an owned [`Cow`](alloc::borrow::Cow).
+    ///
+    /// This always returns [`Cow::Owned`](alloc::borrow::Cow::Owned). A
+    /// `Cow::Borrowed` would have to borrow the referent for `'static`, but
+    /// the referent is only valid while `self` is alive. Like
+    /// [`Self::to_arc`], the returned value doesn't prevent the `Cryo` or
+    /// `CryoMut` from being dropped.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// use std::borrow::Cow;
+    ///
+    /// let snapshot: Cow<'static, str> = with_cryo("hello", |cryo| cryo.borrow().snapshot());
+    /// assert_eq!(snapshot, "hello");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[inline]
+    pub fn snapshot(&self) -> alloc::borrow::Cow<'static, T>
+    where
+        T: alloc::borrow::ToOwned + 'static,
+    {
+        alloc::borrow::Cow::Owned((**self).to_owned())
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> Deref for CryoMutReadGuard<T, Lock> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.data.as_ref() }
+    }
+}
+
+unsafe impl<T: ?Sized, Lock: crate::Lock> StableDeref for CryoMutReadGuard<T, Lock> {}
+unsafe impl<T: ?Sized, Lock: crate::Lock> CloneStableDeref for CryoMutReadGuard<T, Lock> {}
 
 impl<T: ?Sized, Lock: crate::Lock> Clone for CryoMutReadGuard<T, Lock> {
     #[inline]
     fn clone(&self) -> Self {
         unsafe {
-            self.state().lock.lock_shared();
+            self.lock().clone_shared();
+        }
+        Self {
+            data: self.data,
+            lock: self.lock,
         }
-        Self { state: self.state }
     }
 }
 
@@ -568,20 +1468,328 @@ impl<T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for CryoMutReadGuard<
     }
 }
 
+/// Compares the referents.
+impl<T: ?Sized + PartialEq, Lock: crate::Lock> PartialEq for CryoMutReadGuard<T, Lock> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: ?Sized + Eq, Lock: crate::Lock> Eq for CryoMutReadGuard<T, Lock> {}
+
+/// Compares the referents.
+impl<T: ?Sized + PartialOrd, Lock: crate::Lock> PartialOrd for CryoMutReadGuard<T, Lock> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+/// Compares the referents.
+impl<T: ?Sized + Ord, Lock: crate::Lock> Ord for CryoMutReadGuard<T, Lock> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+/// Compares the referent with a value.
+impl<T: ?Sized + PartialEq, Lock: crate::Lock> PartialEq<T> for CryoMutReadGuard<T, Lock> {
+    #[inline]
+    fn eq(&self, other: &T) -> bool {
+        **self == *other
+    }
+}
+
+/// Compares the referent with a value.
+impl<T: ?Sized + PartialOrd, Lock: crate::Lock> PartialOrd<T> for CryoMutReadGuard<T, Lock> {
+    #[inline]
+    fn partial_cmp(&self, other: &T) -> Option<core::cmp::Ordering> {
+        (**self).partial_cmp(other)
+    }
+}
+
+/// Implement the reverse of `PartialEq<T>` and `PartialOrd<T>` for the guards.
+/// Coherence only allows this for concrete types, so it's provided for
+/// primitive types.
+macro_rules! impl_reverse_cmp {
+    ($($ty:ty),*) => {$(
+        impl_reverse_cmp!(@guard $ty, CryoMutReadGuard);
+        impl_reverse_cmp!(@guard $ty, CryoMutWriteGuard);
+    )*};
+    (@guard $ty:ty, $guard:ident) => {
+        /// Compares the value with the referent.
+        impl<Lock: crate::Lock> PartialEq<$guard<$ty, Lock>> for $ty {
+            #[inline]
+            fn eq(&self, other: &$guard<$ty, Lock>) -> bool {
+                *self == **other
+            }
+        }
+
+        /// Compares the value with the referent.
+        impl<Lock: crate::Lock> PartialOrd<$guard<$ty, Lock>> for $ty {
+            #[inline]
+            fn partial_cmp(&self, other: &$guard<$ty, Lock>) -> Option<core::cmp::Ordering> {
+                self.partial_cmp(&**other)
+            }
+        }
+    };
+}
+
+impl_reverse_cmp!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char, str
+);
+
+/// Hashes the referent.
+impl<T: ?Sized + core::hash::Hash, Lock: crate::Lock> core::hash::Hash
+    for CryoMutReadGuard<T, Lock>
+{
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> core::borrow::Borrow<T> for CryoMutReadGuard<T, Lock> {
+    #[inline]
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+/// Allows looking up `CryoRef<String, _>` keys by `&str`.
+#[cfg(all(feature = "hashbrown", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "hashbrown", feature = "alloc"))))]
+impl<Lock: crate::Lock> hashbrown::Equivalent<CryoMutReadGuard<alloc::string::String, Lock>>
+    for str
+{
+    #[inline]
+    fn equivalent(&self, key: &CryoMutReadGuard<alloc::string::String, Lock>) -> bool {
+        *self == **key
+    }
+}
+
+impl<'g, T: ?Sized, Lock: crate::Lock> IntoIterator for &'g CryoMutReadGuard<T, Lock>
+where
+    &'g T: IntoIterator,
+{
+    type Item = <&'g T as IntoIterator>::Item;
+    type IntoIter = <&'g T as IntoIterator>::IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        (&**self).into_iter()
+    }
+}
+
+/// Reads the referent without copying. [`advance`] narrows the guard's view
+/// of the referent; the shared lock is held until the guard is dropped.
+///
+/// [`advance`]: bytes::Buf::advance
+#[cfg(feature = "bytes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+impl<Lock: crate::Lock> bytes::Buf for CryoMutReadGuard<[u8], Lock> {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn chunk(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.len(),
+            "cannot advance past `remaining`: {:?} <= {:?}",
+            cnt,
+            self.len()
+        );
+        self.data = NonNull::from(&self[cnt..]);
+    }
+}
+
 impl<T: ?Sized, Lock: crate::Lock> Drop for CryoMutReadGuard<T, Lock> {
     #[inline]
     fn drop(&mut self) {
         unsafe {
-            self.state().lock.unlock_shared();
-            // `self.state()` might be invalid beyond this point
+            self.lock().unlock_shared();
+            // `self.lock()` and `self.data` might be invalid beyond this point
         }
     }
 }
 
+impl<T, Lock: crate::Lock> CryoMutReadGuard<Option<T>, Lock> {
+    /// Convert `CryoMutReadGuard<Option<T>, _>` into
+    /// `Option<CryoMutReadGuard<T, _>>`.
+    ///
+    /// Returns a guard pointing to the contained value if the referent is
+    /// `Some(_)`. Otherwise, the shared lock is released, and `None` is
+    /// returned.
+    #[inline]
+    pub fn transpose(self) -> Option<CryoMutReadGuard<T, Lock>> {
+        let data = (*self).as_ref().map(NonNull::from);
+        // Safety: The contained value is borrowed from the referent
+        data.map(|data| unsafe { self.map_data(data) })
+    }
+}
+
+/// Evaluating `OK` fails to compile unless `I < N`.
+struct AssertIndexInBounds<const I: usize, const N: usize>;
+
+impl<const I: usize, const N: usize> AssertIndexInBounds<I, N> {
+    const OK: () = assert!(I < N, "index out of bounds");
+}
+
+impl<T, Lock: crate::Lock, const N: usize> CryoMutReadGuard<[T; N], Lock> {
+    /// Convert `CryoMutReadGuard<[T; N], _>` into a guard pointing to the
+    /// `I`-th element. `I` is bounds-checked at compile time.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&[1, 2, 3], |cryo| {
+    ///     assert_eq!(*cryo.borrow().project_array::<2>(), 3);
+    /// });
+    /// ```
+    #[inline]
+    pub fn project_array<const I: usize>(self) -> CryoMutReadGuard<T, Lock> {
+        let () = AssertIndexInBounds::<I, N>::OK;
+        let data = NonNull::from(&self[I]);
+        // Safety: The element is borrowed from the referent
+        unsafe { self.map_data(data) }
+    }
+}
+
+impl<Lock: crate::Lock> CryoMutReadGuard<str, Lock> {
+    /// Convert `CryoMutReadGuard<str, _>` into a guard pointing to the
+    /// substring `self[range]`.
+    ///
+    /// Returns `None` if `range` is out of bounds or doesn't lie on UTF-8
+    /// character boundaries, in which case the shared lock is released.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo("héllo", |cryo| {
+    ///     assert_eq!(&*cryo.borrow().slice(3..6).unwrap(), "llo");
+    ///     assert!(cryo.borrow().slice(0..2).is_none());
+    /// });
+    /// ```
+    #[inline]
+    pub fn slice(self, range: core::ops::Range<usize>) -> Option<CryoMutReadGuard<str, Lock>> {
+        let data = self.get(range).map(NonNull::from);
+        // Safety: The substring is borrowed from the referent
+        data.map(|data| unsafe { self.map_data(data) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<T: ?Sized, Lock: crate::Lock> CryoMutReadGuard<alloc::boxed::Box<T>, Lock> {
+    /// Convert `CryoMutReadGuard<Box<T>, _>` into `CryoMutReadGuard<T, _>`,
+    /// pointing to the `Box`'s contents.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&Box::new(String::from("hello")), |cryo| {
+    ///     let borrow: CryoRef<String, _> = cryo.borrow().flatten();
+    ///     assert_eq!(*borrow, "hello");
+    /// });
+    /// ```
+    #[inline]
+    pub fn flatten(self) -> CryoMutReadGuard<T, Lock> {
+        let data = NonNull::from(&**self);
+        // Safety: The `Box` can't be mutated or dropped while the shared lock
+        //         is held, so neither can its contents
+        unsafe { self.map_data(data) }
+    }
+}
+
 impl<T: ?Sized, Lock: crate::Lock> CryoMutWriteGuard<T, Lock> {
     #[inline]
-    unsafe fn state(&self) -> &State<T, Lock> {
-        self.state.as_ref()
+    unsafe fn lock(&self) -> &Lock {
+        self.lock.as_ref()
+    }
+
+    /// Replace the data pointer, transferring the ownership of the lock to the
+    /// returned guard.
+    ///
+    /// # Safety
+    ///
+    /// `data` must remain valid and exclusively accessible through the
+    /// returned guard while the exclusive lock is held.
+    #[inline]
+    unsafe fn map_data<U: ?Sized>(self, data: NonNull<U>) -> CryoMutWriteGuard<U, Lock> {
+        let lock = self.lock;
+        core::mem::forget(self);
+        CryoMutWriteGuard {
+            data,
+            lock,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get a raw pointer to the referent.
+    ///
+    /// The pointer is valid for reads and writes as long as `this` is alive
+    /// and not otherwise accessed.
+    ///
+    /// This is an associated function so that it doesn't shadow the
+    /// referent's methods, such as [`slice::as_mut_ptr`].
+    #[inline]
+    pub fn as_mut_ptr(this: &mut Self) -> *mut T {
+        this.data.as_ptr()
+    }
+
+    /// Get a [`NonNull`] pointer to the referent.
+    ///
+    /// The pointer is valid for reads and writes as long as `this` is alive
+    /// and not otherwise accessed.
+    #[inline]
+    pub fn as_non_null(this: &mut Self) -> NonNull<T> {
+        this.data
+    }
+}
+
+impl<T, Lock: crate::Lock> CryoMutWriteGuard<core::mem::MaybeUninit<T>, Lock> {
+    /// Convert `CryoMutWriteGuard<MaybeUninit<T>, _>` into
+    /// `CryoMutWriteGuard<T, _>`, keeping the exclusive lock.
+    ///
+    /// # Safety
+    ///
+    /// The referent must be initialized. See
+    /// [`MaybeUninit::assume_init`](core::mem::MaybeUninit::assume_init).
+    #[inline]
+    pub unsafe fn assume_init(self) -> CryoMutWriteGuard<T, Lock> {
+        let data = self.data.cast();
+        self.map_data(data)
+    }
+
+    /// Initialize the referent with `value` and return a guard pointing to
+    /// the initialized value, keeping the exclusive lock.
+    ///
+    /// The old contents are overwritten without being dropped. This shadows
+    /// [`MaybeUninit::write`](core::mem::MaybeUninit::write), which returns
+    /// `&mut T` instead.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// let mut cell = MaybeUninit::uninit();
+    /// with_cryo(&mut cell, |cryo_mut| {
+    ///     let mut guard: CryoMutWriteGuard<u32, _> = cryo_mut.write().write(1);
+    ///     *guard += 1;
+    /// });
+    /// assert_eq!(unsafe { cell.assume_init() }, 2);
+    /// ```
+    #[inline]
+    pub fn write(mut self, value: T) -> CryoMutWriteGuard<T, Lock> {
+        (*self).write(value);
+        // Safety: Just initialized
+        unsafe { self.assume_init() }
     }
 }
 
@@ -590,14 +1798,14 @@ impl<T: ?Sized, Lock: crate::Lock> Deref for CryoMutWriteGuard<T, Lock> {
 
     #[inline]
     fn deref(&self) -> &Self::Target {
-        unsafe { self.state().data.as_ref() }
+        unsafe { self.data.as_ref() }
     }
 }
 
 impl<T: ?Sized, Lock: crate::Lock> DerefMut for CryoMutWriteGuard<T, Lock> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { &mut *self.state().data.as_ptr() }
+        unsafe { self.data.as_mut() }
     }
 }
 
@@ -611,12 +1819,224 @@ impl<T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for CryoMutWriteGuard
     }
 }
 
+/// Implement a compound assignment operator trait for `CryoMutWriteGuard` by
+/// applying it to the referent.
+macro_rules! impl_op_assign {
+    ($($trait:ident::$method:ident),*) => {$(
+        /// Applies the operator to the referent.
+        impl<T: ?Sized + core::ops::$trait<Rhs>, Rhs, Lock: crate::Lock> core::ops::$trait<Rhs>
+            for CryoMutWriteGuard<T, Lock>
+        {
+            #[inline]
+            fn $method(&mut self, rhs: Rhs) {
+                (**self).$method(rhs);
+            }
+        }
+    )*};
+}
+
+impl_op_assign!(
+    AddAssign::add_assign,
+    SubAssign::sub_assign,
+    MulAssign::mul_assign,
+    DivAssign::div_assign,
+    RemAssign::rem_assign,
+    BitAndAssign::bitand_assign,
+    BitOrAssign::bitor_assign,
+    BitXorAssign::bitxor_assign,
+    ShlAssign::shl_assign,
+    ShrAssign::shr_assign
+);
+
+/// Compares the referent with a value.
+impl<T: ?Sized + PartialEq, Lock: crate::Lock> PartialEq<T> for CryoMutWriteGuard<T, Lock> {
+    #[inline]
+    fn eq(&self, other: &T) -> bool {
+        **self == *other
+    }
+}
+
+/// Compares the referent with a value.
+impl<T: ?Sized + PartialOrd, Lock: crate::Lock> PartialOrd<T> for CryoMutWriteGuard<T, Lock> {
+    #[inline]
+    fn partial_cmp(&self, other: &T) -> Option<core::cmp::Ordering> {
+        (**self).partial_cmp(other)
+    }
+}
+
+impl<'g, T: ?Sized, Lock: crate::Lock> IntoIterator for &'g CryoMutWriteGuard<T, Lock>
+where
+    &'g T: IntoIterator,
+{
+    type Item = <&'g T as IntoIterator>::Item;
+    type IntoIter = <&'g T as IntoIterator>::IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        (&**self).into_iter()
+    }
+}
+
+impl<'g, T: ?Sized, Lock: crate::Lock> IntoIterator for &'g mut CryoMutWriteGuard<T, Lock>
+where
+    &'g mut T: IntoIterator,
+{
+    type Item = <&'g mut T as IntoIterator>::Item;
+    type IntoIter = <&'g mut T as IntoIterator>::IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        (&mut **self).into_iter()
+    }
+}
+
+/// Forwards to the referent. `T: Unpin` is required because the referent
+/// isn't pinned.
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+impl<T, Item, Lock> futures_sink::Sink<Item> for CryoMutWriteGuard<T, Lock>
+where
+    T: ?Sized + futures_sink::Sink<Item> + Unpin,
+    Lock: crate::Lock,
+{
+    type Error = T::Error;
+
+    #[inline]
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Result<(), Self::Error>> {
+        Pin::new(&mut **self.get_mut()).poll_ready(cx)
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        Pin::new(&mut **self.get_mut()).start_send(item)
+    }
+
+    #[inline]
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Result<(), Self::Error>> {
+        Pin::new(&mut **self.get_mut()).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Result<(), Self::Error>> {
+        Pin::new(&mut **self.get_mut()).poll_close(cx)
+    }
+}
+
+/// Forwards to the referent. `T: Unpin` is required because the referent
+/// isn't pinned.
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+impl<T, Lock> futures_core::Stream for CryoMutWriteGuard<T, Lock>
+where
+    T: ?Sized + futures_core::Stream + Unpin,
+    Lock: crate::Lock,
+{
+    type Item = T::Item;
+
+    #[inline]
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Option<Self::Item>> {
+        Pin::new(&mut **self.get_mut()).poll_next(cx)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (**self).size_hint()
+    }
+}
+
 impl<T: ?Sized, Lock: crate::Lock> Drop for CryoMutWriteGuard<T, Lock> {
     #[inline]
     fn drop(&mut self) {
         unsafe {
-            self.state().lock.unlock_exclusive();
-            // `self.state()` might be invalid beyond this point
+            self.lock().unlock_exclusive();
+            // `self.lock()` and `self.data` might be invalid beyond this point
+        }
+    }
+}
+
+impl<T, Lock: crate::Lock> CryoMutWriteGuard<Option<T>, Lock> {
+    /// Convert `CryoMutWriteGuard<Option<T>, _>` into
+    /// `Option<CryoMutWriteGuard<T, _>>`.
+    ///
+    /// Returns a guard pointing to the contained value if the referent is
+    /// `Some(_)`. Otherwise, the exclusive lock is released, and `None` is
+    /// returned.
+    #[inline]
+    pub fn transpose(mut self) -> Option<CryoMutWriteGuard<T, Lock>> {
+        let data = (*self).as_mut().map(NonNull::from);
+        // Safety: The contained value is mutably borrowed from the referent
+        data.map(|data| unsafe { self.map_data(data) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized, Lock: crate::Lock> CryoMutWriteGuard<Pin<alloc::boxed::Box<T>>, Lock> {
+    /// Get a pinned mutable reference to the boxed referent, e.g., to poll a
+    /// `Pin<Box<dyn Future>>` stored in a [`CryoMut`].
+    ///
+    /// This is sound because the referent is already pinned by the `Box`;
+    /// only the `Pin<Box<T>>` itself is frozen by the `CryoMut`.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// use futures::task::noop_waker;
+    /// use std::{future::Future, pin::Pin, task::{Context, Poll}};
+    ///
+    /// let mut fut: Pin<Box<dyn Future<Output = u32> + Send>> = Box::pin(async { 42 });
+    /// with_cryo((&mut fut, lock_ty::<SyncLock>()), |cryo_mut| {
+    ///     let mut guard = cryo_mut.write();
+    ///     std::thread::spawn(move || {
+    ///         let waker = noop_waker();
+    ///         let mut cx = Context::from_waker(&waker);
+    ///         assert_eq!(guard.poll_unpin_guard().poll(&mut cx), Poll::Ready(42));
+    ///     });
+    /// });
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[inline]
+    pub fn poll_unpin_guard(&mut self) -> Pin<&mut T> {
+        (**self).as_mut()
+    }
+}
+
+/// Extension methods for `Option<`[`CryoRef`]`>`, such as the one returned by
+/// [`Cryo::try_borrow`].
+pub trait CryoRefOptionExt<T: ?Sized>: private::Sealed {
+    /// Return `true` if `self` is `Some(x)` and `*x == *value`.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&42, |cryo| {
+    ///     assert!(cryo.try_borrow().contains(&42));
+    ///     assert!(!cryo.try_borrow().contains(&43));
+    /// });
+    /// ```
+    fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq;
+}
+
+impl<T: ?Sized, Lock: crate::Lock> CryoRefOptionExt<T> for Option<CryoMutReadGuard<T, Lock>> {
+    #[inline]
+    fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        match self {
+            Some(x) => **x == *value,
+            None => false,
         }
     }
 }
@@ -625,6 +2045,9 @@ impl<T: ?Sized, Lock: crate::Lock> Drop for CryoMutWriteGuard<T, Lock> {
 pub trait WithCryo: private::Sealed + Sized {
     type Cryo;
 
+    /// The [`Lock`] type used by [`Self::Cryo`].
+    type Lock: crate::Lock;
+
     /// Call a given function with a constructed [`Cryo`] or [`CryoMut`].
     ///
     /// This method is also exposed as a global function [`with_cryo`].
@@ -634,14 +2057,17 @@ pub trait WithCryo: private::Sealed + Sized {
 mod private {
     pub trait Sealed {}
     impl<T: ?Sized> Sealed for &T {}
+    impl<T: ?Sized> Sealed for core::pin::Pin<&T> {}
     impl<T: ?Sized> Sealed for &mut T {}
     impl<T: ?Sized, Lock> Sealed for (&T, Lock) {}
     impl<T: ?Sized, Lock> Sealed for (&mut T, Lock) {}
+    impl<T: ?Sized, Lock: crate::Lock> Sealed for Option<crate::CryoMutReadGuard<T, Lock>> {}
 }
 
 /// Constructs [`Cryo`] with [`LocalLock`] as its [`Lock`] type.
 impl<'a, T: ?Sized> WithCryo for &'a T {
     type Cryo = Cryo<'a, T, LocalLock>;
+    type Lock = LocalLock;
 
     #[inline]
     fn with_cryo<R>(self, f: impl FnOnce(Pin<&Self::Cryo>) -> R) -> R {
@@ -651,9 +2077,30 @@ impl<'a, T: ?Sized> WithCryo for &'a T {
     }
 }
 
+/// Constructs [`Cryo`] with [`LocalLock`] as its [`Lock`] type.
+///
+/// `Cryo` only grants shared access to the referent, so the pinning
+/// guarantee is upheld.
+///
+/// ```
+/// # use cryo::*;
+/// let pinned = Box::pin(42);
+/// with_cryo(pinned.as_ref(), |cryo| assert_eq!(*cryo.borrow(), 42));
+/// ```
+impl<'a, T: ?Sized> WithCryo for Pin<&'a T> {
+    type Cryo = Cryo<'a, T, LocalLock>;
+    type Lock = LocalLock;
+
+    #[inline]
+    fn with_cryo<R>(self, f: impl FnOnce(Pin<&Self::Cryo>) -> R) -> R {
+        self.get_ref().with_cryo(f)
+    }
+}
+
 /// Constructs [`CryoMut`] with [`LocalLock`] as its [`Lock`] type.
 impl<'a, T: ?Sized> WithCryo for &'a mut T {
     type Cryo = CryoMut<'a, T, LocalLock>;
+    type Lock = LocalLock;
 
     #[inline]
     fn with_cryo<R>(self, f: impl FnOnce(Pin<&Self::Cryo>) -> R) -> R {
@@ -666,6 +2113,7 @@ impl<'a, T: ?Sized> WithCryo for &'a mut T {
 /// Constructs [`Cryo`] with a specified [`Lock`] type.
 impl<'a, T: ?Sized, Lock: crate::Lock> WithCryo for (&'a T, LockTyMarker<Lock>) {
     type Cryo = Cryo<'a, T, Lock>;
+    type Lock = Lock;
 
     #[inline]
     fn with_cryo<R>(self, f: impl FnOnce(Pin<&Self::Cryo>) -> R) -> R {
@@ -678,6 +2126,7 @@ impl<'a, T: ?Sized, Lock: crate::Lock> WithCryo for (&'a T, LockTyMarker<Lock>)
 /// Constructs [`CryoMut`] with a specified [`Lock`] type.
 impl<'a, T: ?Sized, Lock: crate::Lock> WithCryo for (&'a mut T, LockTyMarker<Lock>) {
     type Cryo = CryoMut<'a, T, Lock>;
+    type Lock = Lock;
 
     #[inline]
     fn with_cryo<R>(self, f: impl FnOnce(Pin<&Self::Cryo>) -> R) -> R {
@@ -707,8 +2156,549 @@ pub const fn lock_ty<T>() -> LockTyMarker<T> {
 ///
 /// This function is a thin wrapper of [`WithCryo::with_cryo`].
 ///
+/// The cell is dropped after `f` returns and before this function returns.
+/// Thus, when calls are nested, the cells are dropped in the reverse order
+/// of creation.
+///
 /// See [the crate documentation](crate) for examples.
 #[inline]
 pub fn with_cryo<T: WithCryo, R>(x: T, f: impl FnOnce(Pin<&T::Cryo>) -> R) -> R {
     x.with_cryo(f)
 }
+
+/// Call a given function repeatedly with a constructed [`Cryo`] or
+/// [`CryoMut`] until it returns [`ControlFlow::Break`].
+///
+/// The cell is constructed once and shared by all iterations, which makes
+/// this suitable for optimistic loops built on `try_*` methods. As with
+/// [`with_cryo`], the cell is dropped after the last iteration and before
+/// this function returns.
+///
+/// ```
+/// # use cryo::*;
+/// use core::ops::ControlFlow;
+///
+/// let mut cell = 0;
+/// let mut attempts = 0;
+/// let old = with_cryo_retry(&mut cell, |cryo_mut| {
+///     attempts += 1;
+///     match cryo_mut.try_replace(1) {
+///         Ok(old) => ControlFlow::Break(old),
+///         Err(_) => ControlFlow::Continue(()),
+///     }
+/// });
+/// assert_eq!((old, attempts, cell), (0, 1, 1));
+/// ```
+///
+/// [`ControlFlow::Break`]: core::ops::ControlFlow::Break
+#[inline]
+pub fn with_cryo_retry<T: WithCryo, R>(
+    x: T,
+    mut f: impl FnMut(Pin<&T::Cryo>) -> core::ops::ControlFlow<R>,
+) -> R {
+    x.with_cryo(|cryo| loop {
+        if let core::ops::ControlFlow::Break(r) = f(cryo) {
+            break r;
+        }
+    })
+}
+
+/// Call a given function with a constructed [`Cryo`] or [`CryoMut`], catching
+/// a panic in the function.
+///
+/// The cell is dropped after the panic is caught, waiting for guards that
+/// might have been stashed elsewhere. The [`NonUnwindingDrop`] bound ensures
+/// this doesn't unwind, which would invalidate the referent while the guards
+/// are still alive.
+///
+/// As with [`std::panic::catch_unwind`], the referent of a `CryoMut` may be
+/// left in an inconsistent state if the function panics in the middle of a
+/// write.
+///
+/// ```
+/// # use cryo::*;
+/// let mut cell = 0;
+/// let result = with_cryo_catch((&mut cell, lock_ty::<SyncLock>()), |cryo_mut| {
+///     *cryo_mut.write() = 1;
+///     panic!("oops");
+/// });
+/// assert!(result.is_err());
+/// assert_eq!(cell, 1);
+/// ```
+///
+/// [`LocalLock`] doesn't implement `NonUnwindingDrop`:
+///
+/// ```compile_fail,E0277
+/// # use cryo::*;
+/// let _ = with_cryo_catch(&42, |cryo| *cryo.borrow());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn with_cryo_catch<T: WithCryo, R>(
+    x: T,
+    f: impl FnOnce(Pin<&T::Cryo>) -> R,
+) -> std::thread::Result<R>
+where
+    T::Lock: NonUnwindingDrop,
+{
+    x.with_cryo(|cryo| std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| f(cryo))))
+}
+
+/// Call a given function with a [`CryoRef`] pointing to `x` and a
+/// cancellation flag to share with workers holding clones of the `CryoRef`.
+///
+/// The flag is itself a `CryoRef<AtomicBool, SyncLock>`, so it can be cloned
+/// and sent to `'static` workers along with the `CryoRef`. `f` may set it to
+/// ask the workers to exit and drop their `CryoRef`s, which unblocks the
+/// `Cryo`'s destructor. If `f` panics, the flag is set automatically before
+/// waiting for the workers.
+///
+/// ```
+/// # use cryo::*;
+/// use std::sync::atomic::Ordering;
+///
+/// let data = [1, 2, 3];
+/// with_cryo_cancellable(&data, |borrow, cancelled| {
+///     let flag = cancelled.clone();
+///     std::thread::spawn(move || {
+///         while !flag.load(Ordering::Acquire) {
+///             assert_eq!(borrow.len(), 3);
+///             std::thread::yield_now();
+///         }
+///     });
+///
+///     // Ask the worker to exit before waiting for it
+///     cancelled.store(true, Ordering::Release);
+/// });
+/// ```
+///
+/// [`AtomicBool`]: core::sync::atomic::AtomicBool
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn with_cryo_cancellable<T: ?Sized, R>(
+    x: &T,
+    f: impl FnOnce(CryoRef<T, SyncLock>, CryoRef<core::sync::atomic::AtomicBool, SyncLock>) -> R,
+) -> R {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    struct CancelOnUnwind<'a>(&'a AtomicBool);
+
+    impl Drop for CancelOnUnwind<'_> {
+        fn drop(&mut self) {
+            if std::thread::panicking() {
+                self.0.store(true, Ordering::Release);
+            }
+        }
+    }
+
+    let cancelled = AtomicBool::new(false);
+    with_cryo((&cancelled, lock_ty::<SyncLock>()), |flag| {
+        with_cryo((x, lock_ty::<SyncLock>()), |cryo| {
+            // Dropped before `cryo` starts waiting for the workers
+            let _cancel_on_unwind = CancelOnUnwind(flag.get());
+            f(cryo.borrow(), flag.borrow())
+        })
+    })
+}
+
+/// Call a given function with a constructed [`Cryo`] that uses a specified
+/// [`Lock`] type.
+///
+/// This is equivalent to `with_cryo((x, lock_ty::<Lock>()), f)`.
+///
+/// ```
+/// # use cryo::*;
+/// let cell = 42;
+/// with_cryo_as::<SyncLock, _, _>(&cell, |cryo| {
+///     assert_eq!(*cryo.borrow(), 42);
+/// });
+/// ```
+#[inline]
+pub fn with_cryo_as<Lock: crate::Lock, T: ?Sized, R>(
+    x: &T,
+    f: impl FnOnce(Pin<&Cryo<'_, T, Lock>>) -> R,
+) -> R {
+    (x, lock_ty::<Lock>()).with_cryo(f)
+}
+
+/// Call a given function with a constructed [`CryoMut`] that uses a specified
+/// [`Lock`] type.
+///
+/// This is equivalent to `with_cryo((x, lock_ty::<Lock>()), f)`.
+///
+/// ```
+/// # use cryo::*;
+/// let mut cell = 42;
+/// with_cryo_mut_as::<SyncLock, _, _>(&mut cell, |cryo_mut| {
+///     *cryo_mut.write() = 43;
+/// });
+/// assert_eq!(cell, 43);
+/// ```
+#[inline]
+pub fn with_cryo_mut_as<Lock: crate::Lock, T: ?Sized, R>(
+    x: &mut T,
+    f: impl FnOnce(Pin<&CryoMut<'_, T, Lock>>) -> R,
+) -> R {
+    (x, lock_ty::<Lock>()).with_cryo(f)
+}
+
+/// Acquire write (exclusive) locks on two [`CryoMut`]s and call a given
+/// function with both referents.
+///
+/// The locks are acquired in the order of the `CryoMut`s' addresses, so
+/// threads locking the same pair in opposite argument orders can't deadlock
+/// each other. Both locks are released before this function returns or
+/// unwinds.
+///
+/// # Panics
+///
+/// Panics if `a` and `b` are the same `CryoMut`.
+///
+/// ```
+/// # use cryo::*;
+/// with_cryo(&mut 1, |a| {
+///     with_cryo(&mut 2, |b| {
+///         lock_both(a, b, |a, b| std::mem::swap(a, b));
+///         assert_eq!((*a.read(), *b.read()), (2, 1));
+///     });
+/// });
+/// ```
+#[inline]
+pub fn lock_both<A: ?Sized, B: ?Sized, Lock: crate::Lock, R>(
+    a: Pin<&CryoMut<'_, A, Lock>>,
+    b: Pin<&CryoMut<'_, B, Lock>>,
+    f: impl FnOnce(&mut A, &mut B) -> R,
+) -> R {
+    let a_addr = &*a as *const CryoMut<'_, A, Lock> as *const () as usize;
+    let b_addr = &*b as *const CryoMut<'_, B, Lock> as *const () as usize;
+    assert_ne!(a_addr, b_addr, "attempted to lock the same `CryoMut` twice");
+
+    let (mut a_guard, mut b_guard);
+    if a_addr < b_addr {
+        a_guard = a.write();
+        b_guard = b.write();
+    } else {
+        b_guard = b.write();
+        a_guard = a.write();
+    }
+    f(&mut a_guard, &mut b_guard)
+}
+
+/// Reborrow `x` immutably and call a given function with a [`Cryo`]
+/// wrapping it.
+///
+/// [`WithCryo`] is implemented for `&mut T` to construct a [`CryoMut`]. This
+/// function constructs a read-only `Cryo` instead, whose [`Cryo::borrow`]
+/// can't fail due to a write lock.
+///
+/// ```
+/// # use cryo::*;
+/// let mut cell = vec![1, 2, 3];
+/// let x = &mut cell;
+/// let sum = cryo_shared(x, |cryo| {
+///     let borrows: Vec<_> = (0..3).map(|_| cryo.borrow()).collect();
+///     borrows.iter().map(|b| b[0]).sum::<i32>()
+/// });
+/// assert_eq!(sum, 3);
+/// x.push(4);
+/// ```
+#[inline]
+pub fn cryo_shared<T: ?Sized, R>(
+    x: &mut T,
+    f: impl FnOnce(Pin<&Cryo<'_, T, LocalLock>>) -> R,
+) -> R {
+    with_cryo(&*x, f)
+}
+
+/// Call a given function with a [`Cryo`] borrowing the data protected by a
+/// locked mutex, such as [`parking_lot::MutexGuard`].
+///
+/// The guard is mutably borrowed for the duration of the call, so the mutex
+/// can't be unlocked (e.g., by `MutexGuard::unlocked`) and the data can't be
+/// mutated through it until the `Cryo` is dropped, which waits for all
+/// [`CryoRef`]s to be dropped.
+///
+/// [`parking_lot::MutexGuard`]: https://docs.rs/parking_lot/0.11/parking_lot/type.MutexGuard.html
+#[cfg(feature = "lock_api")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lock_api")))]
+#[inline]
+pub fn with_cryo_mutex_guard<M: lock_api::RawMutex, T: ?Sized, R>(
+    guard: &mut lock_api::MutexGuard<'_, M, T>,
+    f: impl FnOnce(Pin<&Cryo<'_, T, LocalLock>>) -> R,
+) -> R {
+    with_cryo(&**guard, f)
+}
+
+/// Take ownership of `owner`, and call a given function with a [`Cryo`]
+/// borrowing the projection of `owner` returned by `project`.
+///
+/// `owner` is kept alive until the `Cryo` is dropped, and then dropped
+/// before this function returns.
+///
+/// ```
+/// # use cryo::*;
+/// struct Config {
+///     name: String,
+///     retries: u32,
+/// }
+///
+/// let config = Config { name: "main".to_owned(), retries: 3 };
+/// let len = with_cryo_project(config, |c| &c.name[..], |cryo| cryo.borrow().len());
+/// assert_eq!(len, 4);
+/// ```
+#[inline]
+pub fn with_cryo_project<O, T: ?Sized, R>(
+    owner: O,
+    project: impl for<'a> FnOnce(&'a O) -> &'a T,
+    f: impl FnOnce(Pin<&Cryo<'_, T, LocalLock>>) -> R,
+) -> R {
+    with_cryo(project(&owner), f)
+}
+
+/// Mutably borrow the contents of a [`RefCell`] and call a given function
+/// with a [`CryoMut`] wrapping them.
+///
+/// The `RefCell` stays mutably borrowed until the `CryoMut` is dropped.
+///
+/// # Panics
+///
+/// Panics if the `RefCell` is currently borrowed.
+///
+/// ```
+/// # use cryo::*;
+/// use std::cell::RefCell;
+///
+/// let cell = RefCell::new(vec![1]);
+/// with_cryo_refcell(&cell, |cryo_mut| cryo_mut.write().push(2));
+/// assert_eq!(*cell.borrow(), [1, 2]);
+/// ```
+///
+/// [`RefCell`]: core::cell::RefCell
+#[inline]
+pub fn with_cryo_refcell<T: ?Sized, R>(
+    cell: &core::cell::RefCell<T>,
+    f: impl FnOnce(Pin<&CryoMut<'_, T, LocalLock>>) -> R,
+) -> R {
+    let mut borrow = cell.borrow_mut();
+    with_cryo(&mut *borrow, f)
+}
+
+/// Immutably borrow the contents of a thread-local [`RefCell`] and call a
+/// given function with a [`CryoRef`] pointing to them.
+///
+/// The `RefCell` stays borrowed until `f` returns and all clones of the
+/// `CryoRef` are dropped.
+///
+/// # Panics
+///
+/// Panics if the `RefCell` is currently mutably borrowed, or if a `CryoRef`
+/// outlives `f` (see [`LocalLock`]).
+///
+/// ```
+/// # use cryo::*;
+/// use std::cell::RefCell;
+///
+/// thread_local! {
+///     static CONFIG: RefCell<String> = RefCell::new(String::from("verbose"));
+/// }
+///
+/// let len = with_cryo_thread_local(&CONFIG, |config| {
+///     let config2 = CryoRef::clone(&config);
+///     (move || config2.len())()
+/// });
+/// assert_eq!(len, 7);
+/// ```
+///
+/// [`RefCell`]: core::cell::RefCell
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn with_cryo_thread_local<T: 'static, R>(
+    tls: &'static std::thread::LocalKey<core::cell::RefCell<T>>,
+    f: impl FnOnce(CryoRef<T, LocalLock>) -> R,
+) -> R {
+    tls.with(|cell| {
+        let borrow = cell.borrow();
+        with_cryo(&*borrow, |cryo| f(cryo.borrow()))
+    })
+}
+
+/// Call a given function with a [`Cryo`] borrowing the contents of an owned
+/// [`Box`], and drop the `Box` after the `Cryo` is dropped.
+///
+/// [`WithCryo`] isn't implemented for `Box<T>` because `Cryo`'s lifetime
+/// parameter would have to be `'static`, allowing [`Cryo::get`] to return a
+/// reference outliving the `Box`. `f` is instead generic over the lifetime.
+///
+/// ```
+/// # use cryo::*;
+/// let len = with_cryo_box(Box::new(String::from("hello")), |cryo| {
+///     cryo.borrow().len()
+/// });
+/// assert_eq!(len, 5);
+/// ```
+///
+/// [`Box`]: alloc::boxed::Box
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[inline]
+#[allow(clippy::boxed_local)] // taking the ownership is the point
+pub fn with_cryo_box<T: ?Sized, R>(
+    x: alloc::boxed::Box<T>,
+    f: impl FnOnce(Pin<&Cryo<'_, T, LocalLock>>) -> R,
+) -> R {
+    with_cryo(&*x, f)
+}
+
+/// Construct a [`Cryo`] for each element of `xs` and call a given function
+/// with a [`CryoRef`] borrowed from each `Cryo`.
+///
+/// The `CryoRef`s are dropped after `f` returns, and then the `Cryo`s are
+/// dropped in reverse order of creation, each blocking until outstanding
+/// clones of its `CryoRef` are dropped. This also happens if `f` or the
+/// construction of a `Cryo` panics.
+///
+/// ```
+/// # use cryo::*;
+/// let (a, b) = (1, 2);
+/// let sum = with_cryo_all::<_, LocalLock, _>(&[&a, &b], |borrows| {
+///     borrows.iter().map(|b| **b).sum::<i32>()
+/// });
+/// assert_eq!(sum, 3);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn with_cryo_all<T: ?Sized, Lock: crate::Lock, R>(
+    xs: &[&T],
+    f: impl FnOnce(&[CryoRef<T, Lock>]) -> R,
+) -> R {
+    use alloc::{boxed::Box, vec::Vec};
+
+    /// Drops the `Cryo`s in reverse order of creation.
+    struct Cells<'a, T: ?Sized, Lock: crate::Lock>(Vec<Pin<Box<Cryo<'a, T, Lock>>>>);
+
+    impl<T: ?Sized, Lock: crate::Lock> Drop for Cells<'_, T, Lock> {
+        fn drop(&mut self) {
+            while let Some(cell) = self.0.pop() {
+                drop(cell);
+            }
+        }
+    }
+
+    let mut cells = Cells(Vec::with_capacity(xs.len()));
+    for &x in xs {
+        // Safety: `cells` is dropped before this function returns or
+        //         unwinds, and `x` outlives this function
+        cells.0.push(Box::pin(unsafe { Cryo::new(x) }));
+    }
+
+    let borrows: Vec<_> = cells.0.iter().map(|cell| cell.as_ref().borrow()).collect();
+    f(&borrows)
+}
+
+/// Call a given function with a [`Cryo`] borrowing the contents of a pinned
+/// [`Box`].
+///
+/// This is equivalent to `with_cryo(x.as_ref(), f)`.
+///
+/// ```
+/// # use cryo::*;
+/// let pinned = Box::pin(String::from("hello"));
+/// let len = with_cryo_pin_box(&pinned, |cryo| cryo.borrow().len());
+/// assert_eq!(len, 5);
+/// ```
+///
+/// [`Box`]: alloc::boxed::Box
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[inline]
+pub fn with_cryo_pin_box<T: ?Sized, R>(
+    x: &Pin<alloc::boxed::Box<T>>,
+    f: impl FnOnce(Pin<&Cryo<'_, T, LocalLock>>) -> R,
+) -> R {
+    with_cryo(x.as_ref(), f)
+}
+
+/// Call a given function with a [`CryoMut`] borrowing the contents of an
+/// owned [`Box`], and return the `Box` after the `CryoMut` is dropped.
+///
+/// See [`with_cryo_box`] for why this is not a [`WithCryo`] implementation.
+///
+/// ```
+/// # use cryo::*;
+/// let (x, ()) = with_cryo_mut_box(Box::new(String::from("hello")), |cryo_mut| {
+///     cryo_mut.write().push('!');
+/// });
+/// assert_eq!(*x, "hello!");
+/// ```
+///
+/// [`Box`]: alloc::boxed::Box
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[inline]
+pub fn with_cryo_mut_box<T: ?Sized, R>(
+    mut x: alloc::boxed::Box<T>,
+    f: impl FnOnce(Pin<&CryoMut<'_, T, LocalLock>>) -> R,
+) -> (alloc::boxed::Box<T>, R) {
+    let output = with_cryo(&mut *x, f);
+    (x, output)
+}
+
+/// Call a given function with a constructed [`Cryo`] or [`CryoMut`] and
+/// drive the returned future to completion before the cell is dropped.
+///
+/// The future is polled on the current thread, which is parked while the
+/// future is pending. The future can't borrow the `Pin<&Cryo>` passed to
+/// `f`, but it can hold [`CryoRef`]s and the references returned by
+/// [`Cryo::get`].
+///
+/// ```
+/// # use cryo::*;
+/// let cell = 42;
+/// let value = with_cryo_block_on(&cell, |cryo| {
+///     let borrow = cryo.borrow();
+///     async move { *borrow }
+/// });
+/// assert_eq!(value, 42);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn with_cryo_block_on<T: WithCryo, Fut: core::future::Future>(
+    x: T,
+    f: impl FnOnce(Pin<&T::Cryo>) -> Fut,
+) -> Fut::Output {
+    x.with_cryo(|cryo| block_on::block_on(f(cryo)))
+}
+
+/// Call a given function with a constructed [`Cryo`] or [`CryoMut`] and
+/// drive the future it returns to completion before the cell is dropped.
+///
+/// Unlike [`with_cryo_block_on`], the future can borrow the `Pin<&Cryo>`
+/// passed to `f` and use it across `.await` points. The future never leaves
+/// this function's frame, so the borrow can't escape. The future is returned
+/// as a `Pin<Box<dyn Future>>` so that its type can depend on the lifetime of
+/// the borrow.
+///
+/// ```
+/// # use cryo::*;
+/// let cell = 42;
+/// let value = with_cryo_async_scoped(&cell, |cryo| {
+///     Box::pin(async move {
+///         let x = *cryo.get();
+///         std::future::ready(()).await;
+///         x + *cryo.borrow()
+///     })
+/// });
+/// assert_eq!(value, 84);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn with_cryo_async_scoped<T: WithCryo, R>(
+    x: T,
+    f: impl for<'c> FnOnce(
+        Pin<&'c T::Cryo>,
+    ) -> Pin<alloc::boxed::Box<dyn core::future::Future<Output = R> + 'c>>,
+) -> R {
+    x.with_cryo(|cryo| block_on::block_on(f(cryo)))
+}
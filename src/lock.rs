@@ -15,22 +15,74 @@ mod stdimp;
 #[cfg(feature = "std")]
 pub use self::stdimp::*;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod condvar;
+#[cfg(feature = "std")]
+pub use self::condvar::*;
+
 #[cfg(feature = "atomic")]
 #[cfg_attr(docsrs, doc(cfg(feature = "atomic")))]
 mod panicking;
 #[cfg(feature = "atomic")]
 pub use self::panicking::*;
 
+#[cfg(feature = "atomic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "atomic")))]
+mod instrumented;
+#[cfg(feature = "atomic")]
+pub use self::instrumented::*;
+
 mod local;
 pub use self::local::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SendMarker(());
 
+/// What a [`Lock`] does when a lock can't be acquired immediately. See
+/// [`Lock::ON_CONTENTION`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Contention {
+    /// Blocks the current thread until the lock is released.
+    ///
+    /// This is also reported by every [`lock_api::RawRwLock`] implementation
+    /// regardless of its actual waiting strategy because `lock_api` doesn't
+    /// expose it. For example, a spin-based `RawRwLock` reports `Block`, not
+    /// [`Spin`](Self::Spin).
+    ///
+    /// [`lock_api::RawRwLock`]: https://docs.rs/lock_api/0.4/lock_api/trait.RawRwLock.html
+    Block,
+    /// Panics.
+    Panic,
+    /// Busy-waits until the lock is released.
+    Spin,
+}
+
+/// A snapshot of a [`Lock`]'s state, taken by a single load of its internal
+/// state where possible. See [`Lock::contention_snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LockState {
+    /// The number of shared locks held.
+    pub num_shared_locks: usize,
+    /// Whether an exclusive lock is held.
+    pub exclusive: bool,
+    /// Whether a thread is blocked waiting for the lock. Always `false` if
+    /// the implementation doesn't track waiters.
+    pub has_waiters: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct NoSendMarker(PhantomData<*mut ()>);
 
 /// A trait for readers-writer locks.
+///
+/// # Safety
+///
+/// The implementation must uphold the mutual exclusion guarantee of a
+/// readers-writer lock: while an exclusive lock is held, no other shared or
+/// exclusive locks can be acquired, and while a shared lock is held, no
+/// exclusive lock can be acquired. In particular, `lock_exclusive` must not
+/// return while there are outstanding shared or exclusive locks.
 pub unsafe trait Lock {
     fn new() -> Self;
 
@@ -51,16 +103,40 @@ pub unsafe trait Lock {
     /// it won't have any effect.
     type UnlockMarker;
 
+    /// What [`Self::lock_shared`] and [`Self::lock_exclusive`] do when the lock
+    /// can't be acquired immediately. Generic code can use this to avoid
+    /// patterns that would panic with a panicking `Lock`.
+    const ON_CONTENTION: Contention;
+
     /// Acquire a shared lock, blocking the current thread until the lock
     /// is acquired.
     ///
+    /// The default implementation calls [`Self::try_lock_shared`] in a spin
+    /// loop, which is suitable for [`Contention::Spin`]. Implementations that
+    /// park the thread or panic on contention must override this method. A
+    /// spin loop never terminates if the lock can only be released by the
+    /// current thread.
+    ///
     /// # Safety
     ///
     /// If [`Self::LockMarker`] is `!`[`Send`], the current thread must be the
     /// same one as `self`'s creator.
-    unsafe fn lock_shared(&self);
+    #[inline]
+    unsafe fn lock_shared(&self) {
+        while !self.try_lock_shared() {
+            core::hint::spin_loop();
+        }
+    }
 
-    /// Acquire a shared lock.
+    /// Attempt to acquire a shared lock.
+    ///
+    /// Returns `false` if the lock is held exclusively or if acquiring it
+    /// would overflow the lock's internal counter. This method should not
+    /// panic in either case so that [`Cryo::try_borrow`] and
+    /// [`CryoMut::try_read`] can report failure gracefully.
+    ///
+    /// [`Cryo::try_borrow`]: crate::Cryo::try_borrow
+    /// [`CryoMut::try_read`]: crate::CryoMut::try_read
     ///
     /// # Safety
     ///
@@ -68,6 +144,26 @@ pub unsafe trait Lock {
     /// same one as `self`'s creator.
     unsafe fn try_lock_shared(&self) -> bool;
 
+    /// Acquire an additional shared lock, blocking the current thread until the
+    /// lock is acquired. This is used by [`CryoMutReadGuard::clone`].
+    ///
+    /// The default implementation calls [`Self::lock_shared`]. This is not
+    /// correct if [`Self::LockMarker`] is `!`[`Send`] and
+    /// [`Self::UnlockMarker`] is [`Send`] because shared locks may be
+    /// transferred to non-creator threads, and such implementations must
+    /// override this method.
+    ///
+    /// [`CryoMutReadGuard::clone`]: crate::CryoMutReadGuard
+    ///
+    /// # Safety
+    ///
+    /// The caller must own a shared lock on `self`, which may have been
+    /// transferred from another thread if [`Self::UnlockMarker`] is [`Send`].
+    #[inline]
+    unsafe fn clone_shared(&self) {
+        self.lock_shared();
+    }
+
     /// Release a shared lock.
     ///
     /// # Safety
@@ -81,11 +177,19 @@ pub unsafe trait Lock {
     /// Acquire an exclusive lock, blocking the current thread until the lock
     /// is acquired.
     ///
+    /// The default implementation calls [`Self::try_lock_exclusive`] in a
+    /// spin loop. See [`Self::lock_shared`].
+    ///
     /// # Safety
     ///
     /// If [`Self::LockMarker`] is `!`[`Send`], the current thread must be the
     /// same one as `self`'s creator.
-    unsafe fn lock_exclusive(&self);
+    #[inline]
+    unsafe fn lock_exclusive(&self) {
+        while !self.try_lock_exclusive() {
+            core::hint::spin_loop();
+        }
+    }
 
     /// Acquire an exclusive lock.
     ///
@@ -95,6 +199,25 @@ pub unsafe trait Lock {
     /// same one as `self`'s creator.
     unsafe fn try_lock_exclusive(&self) -> bool;
 
+    /// Attempt to acquire an exclusive lock, retrying up to `max_spins`
+    /// times while the lock is unavailable. This is used by
+    /// [`CryoMut::try_write_spins`].
+    ///
+    /// The default implementation ignores `max_spins` and calls
+    /// [`Self::try_lock_exclusive`] once.
+    ///
+    /// [`CryoMut::try_write_spins`]: crate::CryoMut::try_write_spins
+    ///
+    /// # Safety
+    ///
+    /// If [`Self::LockMarker`] is `!`[`Send`], the current thread must be the
+    /// same one as `self`'s creator.
+    #[inline]
+    unsafe fn try_lock_exclusive_spin(&self, max_spins: u32) -> bool {
+        let _ = max_spins;
+        self.try_lock_exclusive()
+    }
+
     /// Release an exclusive lock.
     ///
     /// # Safety
@@ -104,16 +227,113 @@ pub unsafe trait Lock {
     /// If [`Self::UnlockMarker`] is `!`[`Send`], the current thread must own an
     /// exclusive lock on `self`.
     unsafe fn unlock_exclusive(&self);
+
+    /// Get the number of shared locks currently held, for diagnostics.
+    ///
+    /// Returns `None` if the implementation doesn't track it. The default
+    /// implementation always returns `None`.
+    #[inline]
+    fn num_shared_locks(&self) -> Option<usize> {
+        None
+    }
+
+    /// Get a consistent snapshot of the lock's state, for diagnostics and
+    /// fairness tuning.
+    ///
+    /// Returns `None` if the implementation doesn't support it. The default
+    /// implementation always returns `None`. The result may be outdated by
+    /// the time it's returned if other threads are operating on the lock.
+    #[inline]
+    fn contention_snapshot(&self) -> Option<LockState> {
+        None
+    }
+}
+
+/// [`Lock`] types whose lock guards ([`CryoMutReadGuard`] and
+/// [`CryoMutWriteGuard`]) can be sent to another thread.
+///
+/// This trait is automatically implemented for all types satisfying the
+/// requirements and lets generic code write `L: SendableGuard` instead of
+/// bounding on [`Lock::UnlockMarker`].
+///
+/// ```
+/// # use cryo::*;
+/// fn spawn_reader<L: SendableGuard + 'static>(borrow: CryoRef<u32, L>) {
+///     std::thread::spawn(move || assert_eq!(*borrow, 42));
+/// }
+///
+/// with_cryo((&42, lock_ty::<SyncLock>()), |cryo| spawn_reader(cryo.borrow()));
+/// ```
+///
+/// [`CryoMutReadGuard`]: crate::CryoMutReadGuard
+/// [`CryoMutWriteGuard`]: crate::CryoMutWriteGuard
+pub trait SendableGuard: Lock + Sync + sealed::UnlockMarkerSend {}
+
+impl<T: Lock + Sync + sealed::UnlockMarkerSend> SendableGuard for T {}
+
+/// [`Lock`] types whose cells ([`Cryo`], [`CryoMut`], and [`CryoCell`]) can be
+/// sent to and shared with another thread.
+///
+/// This trait is automatically implemented for all types satisfying the
+/// requirements and lets generic code write `L: SendableCryo` instead of
+/// bounding on [`Lock::LockMarker`].
+///
+/// [`Cryo`]: crate::Cryo
+/// [`CryoMut`]: crate::CryoMut
+/// [`CryoCell`]: crate::CryoCell
+pub trait SendableCryo: Lock + Send + Sync + sealed::LockMarkerSend {}
+
+impl<T: Lock + Send + Sync + sealed::LockMarkerSend> SendableCryo for T {}
+
+pub(crate) mod sealed {
+    use super::Lock;
+
+    /// Implemented for all [`Lock`]s with a `Send` [`Lock::LockMarker`].
+    ///
+    /// Unlike a `where Self::LockMarker: Send` clause, a supertrait bound on
+    /// this trait is implied wherever the subtrait is, which is what makes
+    /// [`SendableCryo`](super::SendableCryo) usable as a bound on its own.
+    pub trait LockMarkerSend: Lock {}
+
+    impl<T: Lock> LockMarkerSend for T where T::LockMarker: Send {}
+
+    /// Implemented for all [`Lock`]s with a `Send` [`Lock::UnlockMarker`].
+    /// See [`LockMarkerSend`].
+    pub trait UnlockMarkerSend: Lock {}
+
+    impl<T: Lock> UnlockMarkerSend for T where T::UnlockMarker: Send {}
 }
 
+/// [`Lock`] types whose [`Lock::lock_exclusive`] never unwinds. It blocks (or
+/// aborts) until all other locks are released instead.
+///
+/// A cell's destructor calls `lock_exclusive` to wait for outstanding
+/// guards. If it unwinds instead, and the panic is caught, the referent may
+/// be invalidated while the guards are still alive. [`with_cryo_catch`]
+/// requires this trait to rule that out.
+///
+/// [`with_cryo_catch`]: crate::with_cryo_catch
+///
+/// # Safety
+///
+/// `lock_exclusive` must not unwind.
+pub unsafe trait NonUnwindingDrop: Lock {}
+
 #[cfg(feature = "lock_api")]
 #[cfg_attr(docsrs, doc(cfg(feature = "lock_api")))]
 /// This crate's `LockTrait` is automatically implemented for types implementing
 /// [`lock_api::RawRwLock`]
+///
+/// [`Lock::ON_CONTENTION`] is always [`Contention::Block`] for these types.
+/// `lock_api` doesn't tell whether a `RawRwLock` blocks or spins, so
+/// spin-based implementations (e.g., the one behind `spin::RwLock`) report
+/// `Block` as well.
 unsafe impl<T: lock_api::RawRwLock> Lock for T {
     type LockMarker = ();
     type UnlockMarker = T::GuardMarker;
 
+    const ON_CONTENTION: Contention = Contention::Block;
+
     #[inline]
     fn new() -> Self {
         <Self as lock_api::RawRwLock>::INIT
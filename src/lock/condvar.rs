@@ -0,0 +1,143 @@
+use std::{
+    fmt,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+use super::{Contention, Lock, LockState, NonUnwindingDrop, SendMarker};
+
+/// An implementation of [`Lock`] built upon [`std::sync::Mutex`] and
+/// [`std::sync::Condvar`]. Unlike [`SyncLock`](crate::SyncLock), lock and
+/// unlock operations can be done in any threads. Blocks the current thread
+/// on borrow failure.
+///
+/// This lock doesn't implement poisoning. A panic while holding a lock
+/// doesn't prevent subsequent lock operations from succeeding.
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct CondvarLock {
+    /// The number of shared locks or [`EXCLUSIVE`].
+    count: Mutex<usize>,
+    /// Signaled when `count` reaches zero.
+    released: Condvar,
+}
+
+const EXCLUSIVE: usize = usize::MAX;
+
+impl fmt::Debug for CondvarLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = *self.count();
+        if count == EXCLUSIVE {
+            write!(f, "CondvarLock {{ <locked exclusively> }}")
+        } else {
+            write!(f, "CondvarLock {{ num_shared_locks: {} }}", count)
+        }
+    }
+}
+
+impl CondvarLock {
+    #[inline]
+    fn count(&self) -> MutexGuard<'_, usize> {
+        // `count` is always consistent, so poisoning can be ignored
+        self.count.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[inline]
+    fn wait<'a>(&self, count: MutexGuard<'a, usize>) -> MutexGuard<'a, usize> {
+        self.released
+            .wait(count)
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+unsafe impl Lock for CondvarLock {
+    // Any thread can lock
+    type LockMarker = SendMarker;
+
+    // Any thread can unlock
+    type UnlockMarker = SendMarker;
+
+    const ON_CONTENTION: Contention = Contention::Block;
+
+    #[inline]
+    fn new() -> Self {
+        Self {
+            count: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    unsafe fn lock_shared(&self) {
+        let mut count = self.count();
+        while *count == EXCLUSIVE {
+            count = self.wait(count);
+        }
+        if *count == EXCLUSIVE - 1 {
+            drop(count);
+            panic!("lock counter overflow");
+        }
+        *count += 1;
+    }
+
+    unsafe fn try_lock_shared(&self) -> bool {
+        let mut count = self.count();
+        if *count >= EXCLUSIVE - 1 {
+            false
+        } else {
+            *count += 1;
+            true
+        }
+    }
+
+    unsafe fn unlock_shared(&self) {
+        let mut count = self.count();
+        debug_assert_ne!(*count, 0);
+        debug_assert_ne!(*count, EXCLUSIVE);
+        *count -= 1;
+        if *count == 0 {
+            drop(count);
+            self.released.notify_all();
+        }
+    }
+
+    unsafe fn lock_exclusive(&self) {
+        let mut count = self.count();
+        while *count != 0 {
+            count = self.wait(count);
+        }
+        *count = EXCLUSIVE;
+    }
+
+    unsafe fn try_lock_exclusive(&self) -> bool {
+        let mut count = self.count();
+        if *count != 0 {
+            false
+        } else {
+            *count = EXCLUSIVE;
+            true
+        }
+    }
+
+    unsafe fn unlock_exclusive(&self) {
+        let mut count = self.count();
+        debug_assert_eq!(*count, EXCLUSIVE);
+        *count = 0;
+        drop(count);
+        self.released.notify_all();
+    }
+
+    fn contention_snapshot(&self) -> Option<LockState> {
+        // Waiters on the condition variable aren't tracked
+        Some(match *self.count() {
+            EXCLUSIVE => LockState {
+                exclusive: true,
+                ..LockState::default()
+            },
+            count => LockState {
+                num_shared_locks: count,
+                ..LockState::default()
+            },
+        })
+    }
+}
+
+/// `lock_exclusive` waits on a condition variable until the lock is released.
+unsafe impl NonUnwindingDrop for CondvarLock {}
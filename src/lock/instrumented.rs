@@ -0,0 +1,174 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::{Contention, Lock, LockState, NonUnwindingDrop};
+
+/// A [`Lock`] wrapping another `Lock` and counting the operations performed
+/// on it. Useful for profiling borrow patterns in tests.
+///
+/// ```
+/// # use cryo::*;
+/// with_cryo((&42, lock_ty::<InstrumentedLock<LocalLock>>()), |cryo| {
+///     let borrow = cryo.borrow();
+///     let _borrow2 = borrow.clone();
+///     assert_eq!(cryo.raw_lock().stats().shared_locks, 2);
+/// });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "atomic")))]
+pub struct InstrumentedLock<L> {
+    inner: L,
+    shared_locks: AtomicUsize,
+    exclusive_locks: AtomicUsize,
+    contention_events: AtomicUsize,
+}
+
+/// The counters of an [`InstrumentedLock`].
+#[cfg_attr(docsrs, doc(cfg(feature = "atomic")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LockStats {
+    /// The number of shared locks acquired, including clones of shared locks.
+    pub shared_locks: usize,
+    /// The number of exclusive locks acquired.
+    pub exclusive_locks: usize,
+    /// The number of times a lock couldn't be acquired immediately, including
+    /// failed `try_lock_*` calls.
+    pub contention_events: usize,
+}
+
+impl<L: fmt::Debug> fmt::Debug for InstrumentedLock<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstrumentedLock")
+            .field("inner", &self.inner)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl<L: Lock> Default for InstrumentedLock<L> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> InstrumentedLock<L> {
+    /// Construct an `InstrumentedLock` wrapping `inner`.
+    #[inline]
+    pub const fn with_inner(inner: L) -> Self {
+        Self {
+            inner,
+            shared_locks: AtomicUsize::new(0),
+            exclusive_locks: AtomicUsize::new(0),
+            contention_events: AtomicUsize::new(0),
+        }
+    }
+
+    /// Get a reference to the wrapped `Lock`.
+    #[inline]
+    pub fn inner(&self) -> &L {
+        &self.inner
+    }
+
+    /// Get the current values of the counters.
+    pub fn stats(&self) -> LockStats {
+        LockStats {
+            shared_locks: self.shared_locks.load(Ordering::Relaxed),
+            exclusive_locks: self.exclusive_locks.load(Ordering::Relaxed),
+            contention_events: self.contention_events.load(Ordering::Relaxed),
+        }
+    }
+
+    #[inline]
+    fn count(counter: &AtomicUsize) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn count_try(&self, counter: &AtomicUsize, success: bool) -> bool {
+        Self::count(if success {
+            counter
+        } else {
+            &self.contention_events
+        });
+        success
+    }
+}
+
+unsafe impl<L: Lock> Lock for InstrumentedLock<L> {
+    type LockMarker = L::LockMarker;
+    type UnlockMarker = L::UnlockMarker;
+
+    const ON_CONTENTION: Contention = L::ON_CONTENTION;
+
+    #[inline]
+    fn new() -> Self {
+        Self::with_inner(L::new())
+    }
+
+    #[inline]
+    unsafe fn lock_shared(&self) {
+        if !self.inner.try_lock_shared() {
+            Self::count(&self.contention_events);
+            self.inner.lock_shared();
+        }
+        Self::count(&self.shared_locks);
+    }
+
+    #[inline]
+    unsafe fn try_lock_shared(&self) -> bool {
+        self.count_try(&self.shared_locks, self.inner.try_lock_shared())
+    }
+
+    #[inline]
+    unsafe fn clone_shared(&self) {
+        self.inner.clone_shared();
+        Self::count(&self.shared_locks);
+    }
+
+    #[inline]
+    unsafe fn unlock_shared(&self) {
+        self.inner.unlock_shared();
+    }
+
+    #[inline]
+    unsafe fn lock_exclusive(&self) {
+        if !self.inner.try_lock_exclusive() {
+            Self::count(&self.contention_events);
+            self.inner.lock_exclusive();
+        }
+        Self::count(&self.exclusive_locks);
+    }
+
+    #[inline]
+    unsafe fn try_lock_exclusive(&self) -> bool {
+        self.count_try(&self.exclusive_locks, self.inner.try_lock_exclusive())
+    }
+
+    #[inline]
+    unsafe fn try_lock_exclusive_spin(&self, max_spins: u32) -> bool {
+        self.count_try(
+            &self.exclusive_locks,
+            self.inner.try_lock_exclusive_spin(max_spins),
+        )
+    }
+
+    #[inline]
+    unsafe fn unlock_exclusive(&self) {
+        self.inner.unlock_exclusive();
+    }
+
+    #[inline]
+    fn num_shared_locks(&self) -> Option<usize> {
+        self.inner.num_shared_locks()
+    }
+
+    #[inline]
+    fn contention_snapshot(&self) -> Option<LockState> {
+        self.inner.contention_snapshot()
+    }
+}
+
+/// `lock_exclusive` only adds counter updates to `L`'s.
+unsafe impl<L: NonUnwindingDrop> NonUnwindingDrop for InstrumentedLock<L> {}
@@ -1,13 +1,13 @@
 use core::{cell::Cell, debug_assert_eq, fmt};
 
-use super::{Lock, NoSendMarker};
+use super::{Contention, Lock, LockState, NoSendMarker};
 
 /// A single-thread implementation of [`Lock`]. Panics on borrow failure.
 pub struct LocalLock {
     count: Cell<usize>,
 }
 
-const EXCLUSIVE: usize = usize::max_value();
+const EXCLUSIVE: usize = usize::MAX;
 
 impl fmt::Debug for LocalLock {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -19,17 +19,37 @@ impl fmt::Debug for LocalLock {
     }
 }
 
-unsafe impl Lock for LocalLock {
+impl LocalLock {
+    /// Construct a `LocalLock`.
+    ///
+    /// Unlike [`Lock::new`], this is a `const fn`.
     #[inline]
-    fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
             count: Cell::new(0),
         }
     }
+}
+
+impl Default for LocalLock {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Lock for LocalLock {
+    #[inline]
+    fn new() -> Self {
+        // Calls the inherent `const fn`
+        Self::new()
+    }
 
     type LockMarker = NoSendMarker;
     type UnlockMarker = NoSendMarker;
 
+    const ON_CONTENTION: Contention = Contention::Panic;
+
     #[inline]
     unsafe fn lock_shared(&self) {
         let count = &self.count;
@@ -86,6 +106,28 @@ unsafe impl Lock for LocalLock {
         debug_assert_eq!(self.count.get(), EXCLUSIVE);
         self.count.set(0);
     }
+
+    #[inline]
+    fn num_shared_locks(&self) -> Option<usize> {
+        match self.count.get() {
+            EXCLUSIVE => Some(0),
+            count => Some(count),
+        }
+    }
+
+    #[inline]
+    fn contention_snapshot(&self) -> Option<LockState> {
+        Some(match self.count.get() {
+            EXCLUSIVE => LockState {
+                exclusive: true,
+                ..LockState::default()
+            },
+            count => LockState {
+                num_shared_locks: count,
+                ..LockState::default()
+            },
+        })
+    }
 }
 
 #[cold]
@@ -3,46 +3,103 @@ use core::{
     sync::atomic::{AtomicUsize, Ordering},
 };
 
-use super::{Lock, SendMarker};
+use super::{Contention, Lock, LockState, SendMarker};
 
 /// An implementation of [`Lock`] that uses atomic operations. Panics on borrow
 /// failure.
+///
+/// If the `atomic-block` feature is enabled, it spin-waits on borrow failure
+/// instead of panicking.
+///
+/// This is [`AtomicLockN`] with the largest supported shared lock limit.
 #[cfg_attr(docsrs, doc(cfg(feature = "atomic")))]
-pub struct AtomicLock {
+pub type AtomicLock = AtomicLockN<MAX_SHARED_LOCKS>;
+
+/// [`AtomicLock`] with a configurable limit on the number of simultaneous
+/// shared locks.
+///
+/// `try_lock_shared` fails once `MAX` shared locks are held. This can be used
+/// to catch runaway shared borrows early. `MAX` must not exceed the limit of
+/// [`AtomicLock`] (half the range of `usize`); larger values are rejected at
+/// compile time.
+///
+/// Like [`AtomicLock`], `lock_shared` panics (or spin-waits with
+/// `atomic-block`) when the limit is reached.
+#[cfg_attr(docsrs, doc(cfg(feature = "atomic")))]
+pub struct AtomicLockN<const MAX: usize> {
     count: AtomicUsize,
 }
 
-const EXCLUSIVE_FLAG: usize = !(usize::max_value() >> 1);
+const EXCLUSIVE_FLAG: usize = !(usize::MAX >> 1);
+
+// Technically, it can have `EXCLUSIVE_FLAG - 1` shared borrows, but we let it
+// fail earlier so that the counter won't overflow.
+const MAX_SHARED_LOCKS: usize = EXCLUSIVE_FLAG / 2;
 
-impl fmt::Debug for AtomicLock {
+impl<const MAX: usize> fmt::Debug for AtomicLockN<MAX> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if MAX == MAX_SHARED_LOCKS {
+            f.write_str("AtomicLock")?;
+        } else {
+            write!(f, "AtomicLockN<{}>", MAX)?;
+        }
         let count = self.count.load(Ordering::Relaxed);
         if (count & EXCLUSIVE_FLAG) != 0 {
-            write!(f, "AtomicLock {{ <locked exclusively> }}",)
+            write!(f, " {{ <locked exclusively> }}",)
         } else {
-            write!(f, "AtomicLock {{ num_shared_locks: {} }}", count)
+            write!(f, " {{ num_shared_locks: {} }}", count)
         }
     }
 }
 
-unsafe impl Lock for AtomicLock {
+impl<const MAX: usize> AtomicLockN<MAX> {
+    /// Evaluating this fails to compile if `MAX` is too large.
+    const MAX_OK: () = assert!(
+        MAX <= MAX_SHARED_LOCKS,
+        "`MAX` exceeds the shared lock limit of `AtomicLock`"
+    );
+
+    /// Construct a `AtomicLockN`.
+    ///
+    /// Unlike [`Lock::new`], this is a `const fn`.
+    #[inline]
+    pub const fn new() -> Self {
+        let () = Self::MAX_OK;
+        Self {
+            count: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<const MAX: usize> Default for AtomicLockN<MAX> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const MAX: usize> Lock for AtomicLockN<MAX> {
     // Any thread can lock
     type LockMarker = SendMarker;
 
     // Any thread can unlock
     type UnlockMarker = SendMarker;
 
+    #[cfg(not(feature = "atomic-block"))]
+    const ON_CONTENTION: Contention = Contention::Panic;
+    #[cfg(feature = "atomic-block")]
+    const ON_CONTENTION: Contention = Contention::Spin;
+
     #[inline]
     fn new() -> Self {
-        Self {
-            count: AtomicUsize::new(0),
-        }
+        // Calls the inherent `const fn`
+        Self::new()
     }
 
     #[inline]
     unsafe fn lock_shared(&self) {
         if !self.try_lock_shared() {
-            borrow_fail();
+            borrow_fail(|| self.try_lock_shared());
         }
     }
 
@@ -50,9 +107,7 @@ unsafe impl Lock for AtomicLock {
     unsafe fn try_lock_shared(&self) -> bool {
         let old_count = self.count.fetch_add(1, Ordering::Acquire);
 
-        // Technically, it can have `EXCLUSIVE_FLAG - 1` shared borrows, but
-        // we let it fail earlier so that the counter won't overflow.
-        if old_count < EXCLUSIVE_FLAG / 2 {
+        if old_count < MAX {
             // Success
             return true;
         }
@@ -71,7 +126,7 @@ unsafe impl Lock for AtomicLock {
     #[inline]
     unsafe fn lock_exclusive(&self) {
         if !self.try_lock_exclusive() {
-            borrow_fail();
+            borrow_fail(|| self.try_lock_exclusive());
         }
     }
 
@@ -82,14 +137,60 @@ unsafe impl Lock for AtomicLock {
             .is_ok()
     }
 
+    #[inline]
+    unsafe fn try_lock_exclusive_spin(&self, max_spins: u32) -> bool {
+        for _ in 0..max_spins {
+            if self.try_lock_exclusive() {
+                return true;
+            }
+            core::hint::spin_loop();
+        }
+        self.try_lock_exclusive()
+    }
+
     #[inline]
     unsafe fn unlock_exclusive(&self) {
         let old_count = self.count.fetch_sub(EXCLUSIVE_FLAG, Ordering::Release);
         debug_assert!((old_count & EXCLUSIVE_FLAG) != 0);
     }
+
+    #[inline]
+    fn num_shared_locks(&self) -> Option<usize> {
+        self.contention_snapshot()
+            .map(|state| state.num_shared_locks)
+    }
+
+    #[inline]
+    fn contention_snapshot(&self) -> Option<LockState> {
+        let count = self.count.load(Ordering::Relaxed);
+        let exclusive = (count & EXCLUSIVE_FLAG) != 0;
+        Some(LockState {
+            num_shared_locks: if exclusive { 0 } else { count },
+            exclusive,
+            // Spinning waiters don't announce themselves
+            has_waiters: false,
+        })
+    }
 }
 
+/// With `atomic-block`, `lock_exclusive` spins until the lock is released.
+#[cfg(feature = "atomic-block")]
+unsafe impl<const MAX: usize> super::NonUnwindingDrop for AtomicLockN<MAX> {}
+
+#[cfg(not(feature = "atomic-block"))]
 #[cold]
-fn borrow_fail() -> ! {
+fn borrow_fail(_try_lock: impl FnMut() -> bool) -> ! {
     panic!("locked")
 }
+
+#[cfg(feature = "atomic-block")]
+#[cold]
+fn borrow_fail(mut try_lock: impl FnMut() -> bool) {
+    let mut backoff = 1u32;
+    while !try_lock() {
+        for _ in 0..backoff {
+            core::hint::spin_loop();
+        }
+        backoff = (backoff * 2).min(1 << 10);
+    }
+}
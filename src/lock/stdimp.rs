@@ -4,7 +4,7 @@ use std::{
     thread,
 };
 
-use super::{Lock, NoSendMarker, SendMarker};
+use super::{Contention, Lock, LockState, NoSendMarker, NonUnwindingDrop, SendMarker};
 
 /// An implementation of [`Lock`] that uses the synchronization facility
 /// provided by [`::std`]. Lock operations are tied to the creator thread, but
@@ -20,7 +20,7 @@ pub struct SyncLock {
     count: AtomicUsize,
 }
 
-const PARKED_FLAG: usize = !(usize::max_value() >> 1);
+const PARKED_FLAG: usize = !(usize::MAX >> 1);
 const EXCLUSIVE_FLAG: usize = PARKED_FLAG >> 1;
 
 impl fmt::Debug for SyncLock {
@@ -49,6 +49,8 @@ unsafe impl Lock for SyncLock {
     // Any thread can unlock
     type UnlockMarker = SendMarker;
 
+    const ON_CONTENTION: Contention = Contention::Block;
+
     #[inline]
     fn new() -> Self {
         Self {
@@ -65,7 +67,9 @@ unsafe impl Lock for SyncLock {
         let old_count = self.count.fetch_add(1, Ordering::Acquire);
         debug_assert!((old_count & PARKED_FLAG) == 0);
 
-        if old_count < EXCLUSIVE_FLAG - 2 {
+        // Leave a wide margin so that concurrent `clone_shared` calls can't
+        // overflow the counter into `EXCLUSIVE_FLAG`
+        if old_count < EXCLUSIVE_FLAG / 2 {
             // Success
             return;
         }
@@ -81,16 +85,35 @@ unsafe impl Lock for SyncLock {
         let old_count = self.count.fetch_add(1, Ordering::Acquire);
         debug_assert!((old_count & PARKED_FLAG) == 0);
 
-        if old_count < EXCLUSIVE_FLAG - 2 {
+        if old_count < EXCLUSIVE_FLAG / 2 {
             // Success
             return true;
         }
 
-        // Failure; revert the change
+        // Failure (locked exclusively or overflow imminent); revert the change
         self.count.fetch_sub(1, Ordering::Relaxed);
         false
     }
 
+    #[inline]
+    unsafe fn clone_shared(&self) {
+        // `UnlockMarker` is `Send`, so the caller might not be the creator
+        // thread. This is okay because the caller owns a shared lock, and
+        // therefore the lock can't be locked exclusively. If the creator
+        // thread is parked in `lock_exclusive_slow`, it will be unparked when
+        // the new shared lock is released.
+        let old_count = self.count.fetch_add(1, Ordering::Relaxed);
+        debug_assert!((old_count & EXCLUSIVE_FLAG) == 0);
+        debug_assert!((old_count & !PARKED_FLAG) > 0);
+
+        // Leave a wide margin so that concurrent `clone_shared` calls can't
+        // overflow the counter into `EXCLUSIVE_FLAG`
+        if (old_count & !PARKED_FLAG) >= EXCLUSIVE_FLAG / 2 {
+            self.count.fetch_sub(1, Ordering::Relaxed);
+            panic!("lock counter overflow");
+        }
+    }
+
     #[inline]
     unsafe fn unlock_shared(&self) {
         const PARKED_FLAG_P1: usize = 1 | PARKED_FLAG;
@@ -159,12 +182,28 @@ unsafe impl Lock for SyncLock {
             self.owner.unpark();
         }
     }
+
+    #[inline]
+    fn contention_snapshot(&self) -> Option<LockState> {
+        let count = self.count.load(Ordering::Relaxed);
+        let exclusive = (count & EXCLUSIVE_FLAG) != 0;
+        Some(LockState {
+            // A set `EXCLUSIVE_FLAG` may be accompanied by a transient
+            // increment by a failing `try_lock_shared`
+            num_shared_locks: if exclusive { 0 } else { count & !PARKED_FLAG },
+            exclusive,
+            has_waiters: (count & PARKED_FLAG) != 0,
+        })
+    }
 }
 
+/// `lock_exclusive` parks the current thread until the lock is released.
+unsafe impl NonUnwindingDrop for SyncLock {}
+
 impl SyncLock {
     #[cold]
     fn lock_shared_slow(&self, old_count: usize) {
-        if old_count == EXCLUSIVE_FLAG - 2 {
+        if old_count < EXCLUSIVE_FLAG {
             // overflow imminent
             self.count.fetch_sub(1, Ordering::Acquire);
             panic!("lock counter overflow");
@@ -0,0 +1,33 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+//! Re-exports of commonly used items.
+//!
+//! ```
+//! use cryo::prelude::*;
+//!
+//! let cell = 42;
+//! with_cryo((&cell, lock_ty::<LocalLock>()), |cryo| {
+//!     let borrow: CryoRef<i32, _> = cryo.borrow();
+//!     assert_eq!(*borrow, 42);
+//! });
+//! ```
+pub use crate::{
+    lock_ty, with_cryo, Cryo, CryoCell, CryoMut, CryoMutReadGuard, CryoMutWriteGuard, CryoRef,
+    LocalLock, Lock, WithCryo,
+};
+
+#[cfg(feature = "atomic")]
+pub use crate::AtomicLock;
+
+#[cfg(feature = "std")]
+pub use crate::{CondvarLock, SyncLock};
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+pub use crate::{ArcCryo, ArcCryoRef};
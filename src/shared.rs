@@ -0,0 +1,152 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use alloc::{
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{fmt, ops::Deref};
+
+use crate::CryoMutReadGuard;
+
+/// A reference-counted [`CryoMutReadGuard`]. Created by
+/// [`CryoMutReadGuard::into_shared`].
+///
+/// Cloning a `SharedCryoRef` increments the `Arc`'s reference count instead
+/// of acquiring another shared lock. The shared lock is released when the
+/// last clone is dropped, so the `Cryo` or `CryoMut` waits for it.
+///
+/// ```
+/// # use cryo::*;
+/// with_cryo(&42, |cryo| {
+///     let shared = cryo.borrow().into_shared();
+///     let weak = SharedCryoRef::downgrade(&shared);
+///     assert_eq!(*weak.upgrade().unwrap(), 42);
+///     drop(shared);
+///     assert!(weak.upgrade().is_none());
+/// });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", feature = "atomic"))))]
+pub struct SharedCryoRef<T: ?Sized, Lock: crate::Lock> {
+    guard: Arc<CryoMutReadGuard<T, Lock>>,
+}
+
+/// A weak reference to a [`SharedCryoRef`]'s guard. Doesn't keep the shared
+/// lock held.
+#[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", feature = "atomic"))))]
+pub struct WeakCryoRef<T: ?Sized, Lock: crate::Lock> {
+    guard: Weak<CryoMutReadGuard<T, Lock>>,
+}
+
+impl<T: ?Sized, Lock: crate::Lock> SharedCryoRef<T, Lock> {
+    #[inline]
+    pub(crate) fn new(guard: CryoMutReadGuard<T, Lock>) -> Self {
+        Self {
+            guard: Arc::new(guard),
+        }
+    }
+
+    /// Create a [`WeakCryoRef`] pointing to the same guard.
+    #[inline]
+    pub fn downgrade(this: &Self) -> WeakCryoRef<T, Lock> {
+        WeakCryoRef {
+            guard: Arc::downgrade(&this.guard),
+        }
+    }
+
+    /// Get the number of `SharedCryoRef`s sharing the guard.
+    #[inline]
+    pub fn strong_count(this: &Self) -> usize {
+        Arc::strong_count(&this.guard)
+    }
+
+    /// Attempt to take the guard out of the `SharedCryoRef`. Succeeds only if
+    /// this is the last clone.
+    #[inline]
+    pub fn try_unwrap(this: Self) -> Result<CryoMutReadGuard<T, Lock>, Self> {
+        Arc::try_unwrap(this.guard).map_err(|guard| Self { guard })
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> WeakCryoRef<T, Lock> {
+    /// Attempt to upgrade to a [`SharedCryoRef`]. Returns `None` if all
+    /// `SharedCryoRef`s have been dropped and the shared lock released.
+    #[inline]
+    pub fn upgrade(&self) -> Option<SharedCryoRef<T, Lock>> {
+        self.guard.upgrade().map(|guard| SharedCryoRef { guard })
+    }
+
+    /// Attempt to upgrade all given `WeakCryoRef`s. Returns `None` if any of
+    /// them has expired, in which case the ones upgraded so far are dropped.
+    ///
+    /// This is useful for following weak edges in a graph of nodes holding
+    /// references to each other. Using `WeakCryoRef` for back-edges prevents
+    /// reference cycles from keeping shared locks held indefinitely, which
+    /// would deadlock (or panic) when the `Cryo` or `CryoMut` is dropped.
+    ///
+    /// ```
+    /// # use cryo::*;
+    /// with_cryo(&[1, 2], |cryo| {
+    ///     let a = CryoRef::map(cryo.borrow(), |x| &x[0]).into_shared();
+    ///     let b = CryoRef::map(cryo.borrow(), |x| &x[1]).into_shared();
+    ///     let weaks = [SharedCryoRef::downgrade(&a), SharedCryoRef::downgrade(&b)];
+    ///
+    ///     let strongs = WeakCryoRef::upgrade_all(&weaks).unwrap();
+    ///     assert_eq!(*strongs[1], 2);
+    ///     drop(strongs);
+    ///
+    ///     drop(b);
+    ///     assert!(WeakCryoRef::upgrade_all(&weaks).is_none());
+    /// });
+    /// ```
+    pub fn upgrade_all(weaks: &[Self]) -> Option<Vec<SharedCryoRef<T, Lock>>> {
+        weaks.iter().map(Self::upgrade).collect()
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> Deref for SharedCryoRef<T, Lock> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> Clone for SharedCryoRef<T, Lock> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            guard: Arc::clone(&self.guard),
+        }
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> Clone for WeakCryoRef<T, Lock> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            guard: Weak::clone(&self.guard),
+        }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, Lock: crate::Lock> fmt::Debug for SharedCryoRef<T, Lock> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedCryoRef")
+            .field("data", &&**self)
+            .finish()
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock> fmt::Debug for WeakCryoRef<T, Lock> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(WeakCryoRef)")
+    }
+}
@@ -0,0 +1,117 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use core::{fmt, pin::Pin};
+use pin_utils::pin_mut;
+
+use crate::{CryoMut, CryoMutReadGuard, CryoMutWriteGuard};
+
+/// A single-slot handoff built on [`CryoMut`]`<Option<T>>`.
+///
+/// A value is deposited by [`put`] and collected by [`take`]. Like
+/// `CryoMut`, dropping a `CryoSlot` blocks (or panics, depending on `Lock`)
+/// until all guards are released, so a value written through a guard held by
+/// another thread is visible in the borrowed `Option<T>` afterwards.
+///
+/// [`put`]: CryoSlot::put
+/// [`take`]: CryoSlot::take
+pub struct CryoSlot<'a, T, Lock: crate::Lock> {
+    inner: CryoMut<'a, Option<T>, Lock>,
+}
+
+impl<'a, T: 'a, Lock: crate::Lock> CryoSlot<'a, T, Lock> {
+    /// Construct a new `CryoSlot`.
+    ///
+    /// # Safety
+    ///
+    /// The created `CryoSlot` should be dropped before `x` is invalidated.
+    /// See [`CryoMut::new`].
+    #[inline]
+    pub unsafe fn new(x: &'a mut Option<T>) -> Self {
+        Self {
+            inner: CryoMut::new(x),
+        }
+    }
+
+    /// Get the underlying [`CryoMut`].
+    #[inline]
+    pub fn as_cryo_mut(self: Pin<&Self>) -> Pin<&CryoMut<'a, Option<T>, Lock>> {
+        // Safety: `inner` is structurally pinned
+        unsafe { self.map_unchecked(|this| &this.inner) }
+    }
+
+    /// Store a value in the slot, replacing (and dropping) the previous one.
+    ///
+    /// This acquires a write (exclusive) lock.
+    #[inline]
+    pub fn put(self: Pin<&Self>, value: T) {
+        *self.as_cryo_mut().write() = Some(value);
+    }
+
+    /// Remove the value from the slot.
+    ///
+    /// This acquires a write (exclusive) lock.
+    #[inline]
+    pub fn take(self: Pin<&Self>) -> Option<T> {
+        self.as_cryo_mut().write().take()
+    }
+
+    /// Acquire a read (shared) lock on the slot.
+    #[inline]
+    pub fn read(self: Pin<&Self>) -> CryoMutReadGuard<Option<T>, Lock> {
+        self.as_cryo_mut().read()
+    }
+
+    /// Acquire a write (exclusive) lock on the slot.
+    ///
+    /// The guard can be sent to another thread (if `Lock` allows) to let it
+    /// deposit a value by assigning `Some(_)` through it.
+    #[inline]
+    pub fn write(self: Pin<&Self>) -> CryoMutWriteGuard<Option<T>, Lock> {
+        self.as_cryo_mut().write()
+    }
+}
+
+impl<'a, T: fmt::Debug, Lock: crate::Lock> fmt::Debug for CryoSlot<'a, T, Lock> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CryoSlot")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Call a given function with a constructed [`CryoSlot`] that uses a
+/// specified [`Lock`] type.
+///
+/// [`Lock`]: crate::Lock
+///
+/// ```
+/// # use cryo::*;
+/// let mut result = None;
+/// with_cryo_slot::<SyncLock, _, _>(&mut result, |slot| {
+///     let mut guard = slot.write();
+///     std::thread::spawn(move || {
+///         *guard = Some(42);
+///     })
+///     .join()
+///     .unwrap();
+///     assert_eq!(slot.take(), Some(42));
+///     slot.put(43);
+/// });
+/// assert_eq!(result, Some(43));
+/// ```
+#[inline]
+pub fn with_cryo_slot<Lock: crate::Lock, T, R>(
+    x: &mut Option<T>,
+    f: impl FnOnce(Pin<&CryoSlot<'_, T, Lock>>) -> R,
+) -> R {
+    let c = unsafe { CryoSlot::new(x) };
+    pin_mut!(c);
+    f(c.as_ref())
+}
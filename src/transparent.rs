@@ -0,0 +1,88 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use crate::CryoMutReadGuard;
+
+/// A `#[repr(transparent)]` newtype wrapping [`Self::Inner`].
+///
+/// Use [`cryo_transparent!`](crate::cryo_transparent) to define a newtype
+/// implementing this trait safely.
+///
+/// # Safety
+///
+/// `Self` must be a `#[repr(transparent)]` struct whose only non-zero-sized
+/// field is of type `Self::Inner`.
+pub unsafe trait TransparentWrapper {
+    /// The wrapped type.
+    type Inner;
+}
+
+/// Define a `#[repr(transparent)]` newtype implementing
+/// [`TransparentWrapper`].
+///
+/// The macro applies `#[repr(transparent)]` by itself, so the layout
+/// requirement of `TransparentWrapper` is upheld by construction. Only tuple
+/// structs with a single field and no generic parameters are supported.
+///
+/// ```
+/// # use cryo::*;
+/// cryo_transparent! {
+///     #[derive(Debug)]
+///     pub struct Meters(pub f64);
+/// }
+///
+/// with_cryo(&Meters(4.0), |cryo| {
+///     let inner: CryoRef<f64, _> = cryo.borrow().peel_transparent();
+///     assert_eq!(*inner, 4.0);
+/// });
+/// ```
+#[macro_export]
+macro_rules! cryo_transparent {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident($field_vis:vis $inner:ty);
+    ) => {
+        $(#[$meta])*
+        #[repr(transparent)]
+        $vis struct $name($field_vis $inner);
+
+        // Safety: `$name` is `#[repr(transparent)]` and has a single field of
+        //         type `$inner`
+        unsafe impl $crate::TransparentWrapper for $name {
+            type Inner = $inner;
+        }
+    };
+}
+
+impl<T, Lock: crate::Lock> CryoMutReadGuard<T, Lock> {
+    /// Reinterpret the referent as `U`, keeping the shared lock.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be a `#[repr(transparent)]` wrapper of `U` (or have an
+    /// otherwise identical layout and validity invariant), and it must be
+    /// sound to view a `&T` as `&U`, e.g., `T` must not rely on invariants
+    /// that `&U` could be used to break through interior mutability.
+    #[inline]
+    pub unsafe fn transparent<U>(self) -> CryoMutReadGuard<U, Lock> {
+        let data = self.data.cast::<U>();
+        self.map_data(data)
+    }
+
+    /// Convert a guard of a [`TransparentWrapper`] into a guard of the wrapped
+    /// value, keeping the shared lock.
+    #[inline]
+    pub fn peel_transparent(self) -> CryoMutReadGuard<T::Inner, Lock>
+    where
+        T: TransparentWrapper,
+    {
+        // Safety: Guaranteed by `TransparentWrapper`'s contract
+        unsafe { self.transparent() }
+    }
+}
@@ -0,0 +1,179 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    ptr::NonNull,
+};
+use pin_utils::pin_mut;
+
+use crate::{CryoMut, CryoMutReadGuard, CryoMutWriteGuard, LocalLock};
+
+/// A variation of [`CryoMut`] that calls a given function whenever a write
+/// lock is released.
+///
+/// The function is called with the referent *before* the write lock is
+/// released. This is because another lock can't be acquired in its place
+/// (the write lock may be released by a thread that is not allowed to lock
+/// `Lock`), and doing so would allow other writes to slip in between.
+/// Consequently, the function must not access the `WatchedCryoMut`, or it
+/// will deadlock or panic.
+pub struct WatchedCryoMut<'a, T: ?Sized, Lock: crate::Lock, F: Fn(&T)> {
+    // `inner` must be dropped first because outstanding guards may refer to
+    // `on_write`
+    inner: CryoMut<'a, T, Lock>,
+    on_write: F,
+}
+
+impl<'a, T: ?Sized + 'a, Lock: crate::Lock, F: Fn(&T)> WatchedCryoMut<'a, T, Lock, F> {
+    /// Construct a new `WatchedCryoMut`.
+    ///
+    /// # Safety
+    ///
+    /// The created `WatchedCryoMut` should be dropped before `x` is
+    /// invalidated. See [`CryoMut::new`].
+    #[inline]
+    pub unsafe fn new(x: &'a mut T, on_write: F) -> Self {
+        Self {
+            inner: CryoMut::new(x),
+            on_write,
+        }
+    }
+
+    #[inline]
+    fn inner(self: Pin<&Self>) -> Pin<&CryoMut<'a, T, Lock>> {
+        // Safety: `inner` is structurally pinned
+        unsafe { self.map_unchecked(|this| &this.inner) }
+    }
+
+    /// Acquire a read (shared) lock on a `WatchedCryoMut`.
+    #[inline]
+    pub fn read(self: Pin<&Self>) -> CryoMutReadGuard<T, Lock> {
+        self.inner().read()
+    }
+
+    /// Attempt to acquire a read (shared) lock on a `WatchedCryoMut`.
+    #[inline]
+    pub fn try_read(self: Pin<&Self>) -> Option<CryoMutReadGuard<T, Lock>> {
+        self.inner().try_read()
+    }
+
+    /// Acquire a write (exclusive) lock on a `WatchedCryoMut`.
+    #[inline]
+    pub fn write(self: Pin<&Self>) -> WatchedWriteGuard<T, Lock, F> {
+        WatchedWriteGuard {
+            guard: self.inner().write(),
+            on_write: NonNull::from(&self.get_ref().on_write),
+        }
+    }
+
+    /// Attempt to acquire a write (exclusive) lock on a `WatchedCryoMut`.
+    #[inline]
+    pub fn try_write(self: Pin<&Self>) -> Option<WatchedWriteGuard<T, Lock, F>> {
+        let guard = self.inner().try_write()?;
+        Some(WatchedWriteGuard {
+            guard,
+            on_write: NonNull::from(&self.get_ref().on_write),
+        })
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Debug, Lock: crate::Lock, F: Fn(&T)> fmt::Debug
+    for WatchedCryoMut<'a, T, Lock, F>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedCryoMut")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The write lock guard type of [`WatchedCryoMut`].
+pub struct WatchedWriteGuard<T: ?Sized, Lock: crate::Lock, F: Fn(&T)> {
+    guard: CryoMutWriteGuard<T, Lock>,
+    on_write: NonNull<F>,
+}
+
+/// `on_write` is called through `&F` on the thread dropping the guard, hence
+/// `F: Sync`.
+unsafe impl<T: ?Sized, Lock: crate::Lock, F: Fn(&T) + Sync> Send for WatchedWriteGuard<T, Lock, F> where
+    CryoMutWriteGuard<T, Lock>: Send
+{
+}
+
+/// `on_write` is never touched through `&WatchedWriteGuard`.
+unsafe impl<T: ?Sized, Lock: crate::Lock, F: Fn(&T)> Sync for WatchedWriteGuard<T, Lock, F> where
+    CryoMutWriteGuard<T, Lock>: Sync
+{
+}
+
+impl<T: ?Sized, Lock: crate::Lock, F: Fn(&T)> Deref for WatchedWriteGuard<T, Lock, F> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock, F: Fn(&T)> DerefMut for WatchedWriteGuard<T, Lock, F> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, Lock: crate::Lock, F: Fn(&T)> fmt::Debug
+    for WatchedWriteGuard<T, Lock, F>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedWriteGuard")
+            .field("data", &&**self)
+            .finish()
+    }
+}
+
+impl<T: ?Sized, Lock: crate::Lock, F: Fn(&T)> Drop for WatchedWriteGuard<T, Lock, F> {
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: `WatchedCryoMut` outlives the lock, and so does `on_write`.
+        //         The lock is released after this when `self.guard` is
+        //         dropped.
+        let on_write = unsafe { self.on_write.as_ref() };
+        on_write(&self.guard);
+    }
+}
+
+/// Call a given function with a constructed [`WatchedCryoMut`] that uses
+/// [`LocalLock`].
+///
+/// ```
+/// # use cryo::*;
+/// use std::cell::Cell;
+///
+/// let num_writes = Cell::new(0);
+/// let mut cell = 0;
+/// with_cryo_watched(&mut cell, |_| num_writes.set(num_writes.get() + 1), |cryo_mut| {
+///     *cryo_mut.write() += 1;
+///     *cryo_mut.write() += 1;
+/// });
+/// assert_eq!(num_writes.get(), 2);
+/// ```
+#[inline]
+pub fn with_cryo_watched<T: ?Sized, F: Fn(&T), R>(
+    x: &mut T,
+    on_write: F,
+    f: impl FnOnce(Pin<&WatchedCryoMut<'_, T, LocalLock, F>>) -> R,
+) -> R {
+    let c = unsafe { WatchedCryoMut::new(x, on_write) };
+    pin_mut!(c);
+    f(c.as_ref())
+}
@@ -61,3 +61,1112 @@ fn block_on_drop() {
         });
     });
 }
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn to_arc() {
+    let cell = "hello".to_owned();
+    let arc = with_cryo((&cell, lock_ty::<SyncLock>()), |cryo| {
+        let borrow = cryo.borrow();
+        let arc: std::sync::Arc<String> = borrow.to_arc();
+        assert_eq!(*arc, *borrow);
+        assert_ne!(arc.as_ptr(), borrow.as_ptr());
+        arc
+    });
+    drop(cell);
+    assert_eq!(*arc, "hello");
+}
+
+#[test]
+fn snapshot_str() {
+    use std::borrow::Cow;
+    let cell = "hello".to_owned();
+    let snapshot: Cow<'static, str> = with_cryo(cell.as_str(), |cryo| {
+        let borrow: CryoRef<str, _> = cryo.borrow();
+        let snapshot = borrow.snapshot();
+        assert!(matches!(snapshot, Cow::Owned(_)));
+        assert_ne!(snapshot.as_ptr(), borrow.as_ptr());
+        snapshot
+    });
+    drop(cell);
+    assert_eq!(snapshot, "hello");
+}
+
+#[test]
+fn transpose_some() {
+    with_cryo(&Some(42), |cryo| {
+        let borrow: CryoRef<i32, _> = cryo.borrow().transpose().unwrap();
+        assert_eq!(*borrow, 42);
+    });
+}
+
+#[test]
+fn transpose_none() {
+    with_cryo(&None::<i32>, |cryo| {
+        assert!(cryo.borrow().transpose().is_none());
+    });
+}
+
+#[test]
+fn block_on() {
+    let value = with_cryo_block_on(&42, |cryo| {
+        let borrow = cryo.borrow();
+        let get = cryo.get();
+        async move {
+            futures::future::ready(()).await;
+            *borrow + *get
+        }
+    });
+    assert_eq!(value, 84);
+}
+
+#[test]
+fn block_on_pending() {
+    with_cryo_block_on((&42, lock_ty::<SyncLock>()), |cryo| {
+        let borrow = cryo.borrow();
+        let (send, recv) = futures::channel::oneshot::channel();
+        spawn(move || {
+            sleep(Duration::from_millis(50));
+            send.send(*borrow).unwrap();
+        });
+        async move {
+            assert_eq!(recv.await.unwrap(), 42);
+        }
+    });
+}
+
+#[test]
+fn async_scoped() {
+    let value = with_cryo_async_scoped((&42, lock_ty::<SyncLock>()), |cryo| {
+        Box::pin(async move {
+            let mut sum = 0;
+            for _ in 0..3 {
+                // Yield to the executor once
+                let mut yielded = false;
+                futures::future::poll_fn(|cx| {
+                    if std::mem::replace(&mut yielded, true) {
+                        std::task::Poll::Ready(())
+                    } else {
+                        cx.waker().wake_by_ref();
+                        std::task::Poll::Pending
+                    }
+                })
+                .await;
+                sum += *cryo.get();
+                let (send, recv) = futures::channel::oneshot::channel();
+                let borrow = cryo.borrow();
+                spawn(move || send.send(*borrow).unwrap());
+                sum += recv.await.unwrap();
+            }
+            sum
+        })
+    });
+    assert_eq!(value, 42 * 6);
+}
+
+#[test]
+fn try_borrow() {
+    with_cryo(&42, |cryo| {
+        let b1 = cryo.try_borrow().unwrap();
+        let _b2 = cryo.try_borrow().unwrap();
+        assert_eq!(*b1, 42);
+    });
+}
+
+#[test]
+fn try_borrow_contains() {
+    with_cryo("hello", |cryo| {
+        let borrow = cryo.try_borrow();
+        assert!(borrow.contains("hello"));
+        assert!(!borrow.contains("world"));
+        assert!(!None::<CryoRef<str, LocalLock>>.contains("hello"));
+    });
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn buf() {
+    use bytes::Buf;
+    with_cryo((&b"hello world"[..], lock_ty::<SyncLock>()), |cryo| {
+        let mut borrow = cryo.borrow();
+        assert_eq!(borrow.remaining(), 11);
+        assert_eq!(borrow.chunk(), b"hello world");
+        borrow.advance(6);
+        assert_eq!(borrow.remaining(), 5);
+        assert_eq!(borrow.chunk(), b"world");
+        assert_eq!(borrow.get_u8(), b'w');
+        assert_eq!(*borrow, *b"orld");
+        assert_eq!(*cryo.get(), *b"hello world");
+    });
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+#[should_panic]
+fn buf_advance_past_end() {
+    use bytes::Buf;
+    with_cryo(&b"hello"[..], |cryo| {
+        cryo.borrow().advance(6);
+    });
+}
+
+#[test]
+fn get_outlives_cryo() {
+    let cell = vec![1, 2, 3];
+    let (first, len): (&i32, usize) = with_cryo(&cell, |cryo| {
+        let borrow = cryo.borrow();
+        (&cryo.get()[0], borrow.len())
+    });
+    assert_eq!(*first, 1);
+    assert_eq!(len, 3);
+}
+
+#[test]
+fn condvar_lock_send_cryo() {
+    static CELL: usize = 42;
+    let cryo = Box::pin(unsafe { Cryo::<_, CondvarLock>::new(&CELL) });
+    let borrow = spawn(move || {
+        let borrow = cryo.as_ref().borrow();
+        assert_eq!(*borrow, 42);
+        (cryo, borrow)
+    })
+    .join()
+    .unwrap();
+    let (cryo, borrow) = borrow;
+    spawn(move || {
+        sleep(Duration::from_millis(50));
+        drop(borrow);
+    });
+    // Blocks until `borrow` is dropped
+    drop(cryo);
+}
+
+#[test]
+fn condvar_lock_borrow_from_other_threads() {
+    with_cryo((&42, lock_ty::<CondvarLock>()), |cryo| {
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    let borrow = cryo.borrow();
+                    spawn(move || {
+                        sleep(Duration::from_millis(50));
+                        assert_eq!(*borrow, 42);
+                    });
+                });
+            }
+        });
+    });
+}
+
+#[test]
+fn derive_clone_holder() {
+    struct NotClone(u32);
+
+    #[derive(Clone)]
+    struct Holder(CryoRef<NotClone, LocalLock>);
+
+    with_cryo(&NotClone(42), |cryo| {
+        let holder = Holder(cryo.borrow());
+        let holder2 = holder.clone();
+        assert_eq!((holder.0).0, 42);
+        assert_eq!((holder2.0).0, 42);
+    });
+}
+
+#[test]
+fn clone_on_other_thread() {
+    with_cryo((&42, lock_ty::<SyncLock>()), |cryo| {
+        let borrow = cryo.borrow();
+        spawn(move || {
+            let borrow2 = borrow.clone();
+            drop(borrow);
+            sleep(Duration::from_millis(50));
+            assert_eq!(*borrow2, 42);
+        });
+    });
+}
+
+#[test]
+fn flatten_box() {
+    let cell = Box::new(String::from("hello"));
+    with_cryo(&cell, |cryo| {
+        let borrow: CryoRef<Box<String>, _> = cryo.borrow();
+        let flat: CryoRef<String, _> = borrow.flatten();
+        assert_eq!(*flat, "hello");
+        let flat2 = flat.clone();
+        drop(flat);
+        assert_eq!(flat2.len(), 5);
+    });
+}
+
+#[test]
+fn with_cryo_as_sync_lock() {
+    let cell = 42;
+    with_cryo_as::<SyncLock, _, _>(&cell, |cryo| {
+        let borrow = cryo.borrow();
+        spawn(move || assert_eq!(*borrow, 42));
+    });
+}
+
+#[test]
+fn try_borrow_saturated() {
+    /// A lock whose counter is about to overflow
+    struct SaturatedLock;
+
+    unsafe impl Lock for SaturatedLock {
+        type LockMarker = NoSendMarker;
+        type UnlockMarker = NoSendMarker;
+        const ON_CONTENTION: Contention = Contention::Panic;
+
+        fn new() -> Self {
+            Self
+        }
+        unsafe fn lock_shared(&self) {
+            panic!("lock counter overflow");
+        }
+        unsafe fn try_lock_shared(&self) -> bool {
+            false
+        }
+        unsafe fn unlock_shared(&self) {
+            unreachable!()
+        }
+        unsafe fn lock_exclusive(&self) {}
+        unsafe fn try_lock_exclusive(&self) -> bool {
+            true
+        }
+        unsafe fn unlock_exclusive(&self) {}
+    }
+
+    with_cryo((&42, lock_ty::<SaturatedLock>()), |cryo| {
+        assert!(cryo.try_borrow().is_none());
+    });
+}
+
+#[test]
+fn str_slice() {
+    with_cryo("añb", |cryo| {
+        let borrow: CryoRef<str, _> = cryo.borrow();
+        let sub = borrow.clone().slice(1..3).unwrap();
+        assert_eq!(&*sub, "ñ");
+        assert_eq!(&*sub.slice(0..2).unwrap(), "ñ");
+        assert_eq!(&*borrow.clone().slice(0..0).unwrap(), "");
+        assert_eq!(&*borrow.clone().slice(3..4).unwrap(), "b");
+
+        // Crosses a character boundary
+        assert!(borrow.clone().slice(1..2).is_none());
+        assert!(borrow.clone().slice(2..4).is_none());
+        // Out of bounds
+        assert!(borrow.clone().slice(3..5).is_none());
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = borrow.slice(3..1);
+        assert!(reversed.is_none());
+    });
+}
+
+#[cfg(feature = "atomic-block")]
+#[test]
+fn atomic_lock_block() {
+    let cell = 42;
+    let start = std::time::Instant::now();
+    with_cryo((&cell, lock_ty::<AtomicLock>()), |cryo| {
+        let borrow = cryo.borrow();
+        spawn(move || {
+            sleep(Duration::from_millis(50));
+            assert_eq!(*borrow, 42);
+        });
+        // The destructor of `cryo` spins until `borrow` is dropped
+    });
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn deref_into() {
+    let cell = String::from("hello");
+    with_cryo(&cell, |cryo| {
+        let borrow: CryoRef<str, _> = cryo.borrow().deref_into();
+        assert_eq!(&*borrow, "hello");
+        let sub = borrow.slice(1..3).unwrap();
+        assert_eq!(&*sub, "el");
+    });
+}
+
+#[test]
+fn with_cryo_box_string() {
+    let x = with_cryo_box(Box::new(String::from("hello")), |cryo| {
+        let borrow = cryo.borrow();
+        let closure: Box<dyn Fn() -> usize> = Box::new(move || borrow.len());
+        closure()
+    });
+    assert_eq!(x, 5);
+}
+
+#[test]
+fn cmp_hash() {
+    use std::collections::HashSet;
+    let cells = [1, 2, 2];
+    with_cryo(&cells[0], |c0| {
+        with_cryo(&cells[1], |c1| {
+            with_cryo(&cells[2], |c2| {
+                let (b0, b1, b2) = (c0.borrow(), c1.borrow(), c2.borrow());
+                assert!(b0 < b1);
+                assert_eq!(b1, b2);
+                assert_eq!(b0.cmp(&b1), std::cmp::Ordering::Less);
+
+                let set: HashSet<_> = vec![b0, b1, b2].into_iter().collect();
+                assert_eq!(set.len(), 2);
+                assert!(set.contains(&2));
+            });
+        });
+    });
+}
+
+#[cfg(feature = "hashbrown")]
+#[test]
+fn hashbrown_equivalent() {
+    use std::collections::hash_map::RandomState;
+    let keys = [String::from("a"), String::from("b")];
+    with_cryo(&keys[0], |k0| {
+        with_cryo(&keys[1], |k1| {
+            let mut map = hashbrown::HashMap::with_hasher(RandomState::new());
+            map.insert(k0.borrow(), 1);
+            map.insert(k1.borrow(), 2);
+            assert_eq!(map.get("a"), Some(&1));
+            assert_eq!(map.get("b"), Some(&2));
+            assert_eq!(map.get("c"), None);
+        });
+    });
+}
+
+#[test]
+fn project_array() {
+    with_cryo(&[1u32, 2, 3, 4], |cryo| {
+        let borrow: CryoRef<u32, _> = cryo.borrow().project_array::<2>();
+        assert_eq!(*borrow, 3);
+        assert_eq!(*cryo.borrow().project_array::<0>(), 1);
+    });
+}
+
+/// A `Lock` that records the order in which the cells are finalized.
+struct DropOrderLock(usize, LocalLock);
+
+thread_local! {
+    static NEXT_ID: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    static DROP_ORDER: std::cell::RefCell<Vec<usize>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+unsafe impl Lock for DropOrderLock {
+    type LockMarker = NoSendMarker;
+    type UnlockMarker = NoSendMarker;
+    const ON_CONTENTION: Contention = Contention::Panic;
+
+    fn new() -> Self {
+        let id = NEXT_ID.with(|x| x.replace(x.get() + 1));
+        Self(id, LocalLock::new())
+    }
+    unsafe fn lock_shared(&self) {
+        self.1.lock_shared()
+    }
+    unsafe fn try_lock_shared(&self) -> bool {
+        self.1.try_lock_shared()
+    }
+    unsafe fn unlock_shared(&self) {
+        self.1.unlock_shared()
+    }
+    unsafe fn lock_exclusive(&self) {
+        // Called by the destructors of `Cryo` and `CryoMut`
+        DROP_ORDER.with(|x| x.borrow_mut().push(self.0));
+        self.1.lock_exclusive()
+    }
+    unsafe fn try_lock_exclusive(&self) -> bool {
+        self.1.try_lock_exclusive()
+    }
+    unsafe fn unlock_exclusive(&self) {
+        self.1.unlock_exclusive()
+    }
+}
+
+#[test]
+fn nested_drop_order() {
+    let cells = [1, 2, 3];
+    with_cryo((&cells[0], lock_ty::<DropOrderLock>()), |c0| {
+        with_cryo((&cells[1], lock_ty::<DropOrderLock>()), |c1| {
+            with_cryo((&cells[2], lock_ty::<DropOrderLock>()), |c2| {
+                // Borrows of outer cells may be held by inner ones
+                let _b = (c0.borrow(), c1.borrow(), c2.borrow());
+            });
+        });
+    });
+    assert_eq!(DROP_ORDER.with(|x| x.borrow().clone()), [2, 1, 0]);
+}
+
+#[test]
+fn with_cryo_thread_local_read() {
+    use std::cell::RefCell;
+    thread_local! {
+        static CONFIG: RefCell<Vec<u32>> = RefCell::new(vec![1, 2, 3]);
+    }
+
+    let sum = with_cryo_thread_local(&CONFIG, |config| {
+        let nested = |config: CryoRef<Vec<u32>, LocalLock>| config.iter().sum::<u32>();
+        nested(CryoRef::clone(&config)) + config.len() as u32
+    });
+    assert_eq!(sum, 9);
+
+    // The borrow is released afterwards
+    CONFIG.with(|cell| cell.borrow_mut().push(4));
+    assert_eq!(with_cryo_thread_local(&CONFIG, |config| config.len()), 4);
+}
+
+#[test]
+#[should_panic]
+fn with_cryo_thread_local_mutably_borrowed() {
+    use std::cell::RefCell;
+    thread_local! {
+        static CONFIG: RefCell<u32> = const { RefCell::new(0) };
+    }
+
+    CONFIG.with(|cell| {
+        let _borrow = cell.borrow_mut();
+        with_cryo_thread_local(&CONFIG, |_| {});
+    });
+}
+
+#[test]
+fn with_cryo_catch_stashed_guard() {
+    let stash = std::sync::Mutex::new(None);
+    let cell = 42;
+    std::thread::scope(|s| {
+        let result = with_cryo_catch((&cell, lock_ty::<SyncLock>()), |cryo| {
+            let borrow = cryo.borrow();
+            *stash.lock().unwrap() = Some(borrow);
+            s.spawn(|| {
+                sleep(Duration::from_millis(50));
+                // Still valid because the `Cryo` waits for this guard
+                assert_eq!(**stash.lock().unwrap().as_ref().unwrap(), 42);
+                *stash.lock().unwrap() = None;
+            });
+            panic!("oops");
+        });
+        assert!(result.is_err());
+        assert!(stash.lock().unwrap().is_none());
+    });
+}
+
+#[test]
+fn with_cryo_catch_no_panic() {
+    let result = with_cryo_catch((&42, lock_ty::<CondvarLock>()), |cryo| *cryo.borrow());
+    assert_eq!(result.unwrap(), 42);
+}
+
+#[test]
+fn cryo_shared_many_borrows() {
+    let mut cell = String::from("hello");
+    let x = &mut cell;
+    cryo_shared(x, |cryo| {
+        let borrows: Vec<CryoRef<String, _>> = (0..100).map(|_| cryo.borrow()).collect();
+        assert!(borrows.iter().all(|b| **b == "hello"));
+        let clones: Vec<_> = borrows.iter().map(CryoRef::clone).collect();
+        assert_eq!(clones.len(), 100);
+    });
+    x.push('!');
+    assert_eq!(cell, "hello!");
+}
+
+#[test]
+fn with_cryo_all_five() {
+    let cells = [1, 2, 3, 4, 5].map(|x| x.to_string());
+    let refs: Vec<&String> = cells.iter().collect();
+    let joined = with_cryo_all::<_, SyncLock, _>(&refs, |borrows| {
+        assert_eq!(borrows.len(), 5);
+        let clones: Vec<_> = borrows.iter().map(CryoRef::clone).collect();
+        let handle = spawn(move || clones.iter().map(|b| b.as_str()).collect::<String>());
+        handle.join().unwrap()
+    });
+    assert_eq!(joined, "12345");
+}
+
+#[test]
+fn with_cryo_all_drop_order() {
+    let cells = [1, 2, 3];
+    with_cryo_all::<_, DropOrderLock, _>(&[&cells[0], &cells[1], &cells[2]], |borrows| {
+        assert_eq!(borrows.iter().map(|b| **b).collect::<Vec<_>>(), cells);
+    });
+    assert_eq!(DROP_ORDER.with(|x| x.borrow().clone()), [2, 1, 0]);
+}
+
+#[test]
+fn with_cryo_all_drop_order_on_panic() {
+    let cells = [1, 2, 3];
+    let result = std::panic::catch_unwind(|| {
+        with_cryo_all::<_, DropOrderLock, _>(&[&cells[0], &cells[1], &cells[2]], |_| panic!())
+    });
+    assert!(result.is_err());
+    assert_eq!(DROP_ORDER.with(|x| x.borrow().clone()), [2, 1, 0]);
+}
+
+#[test]
+fn with_cryo_all_empty() {
+    assert_eq!(
+        with_cryo_all::<i32, LocalLock, _>(&[], |borrows| borrows.len()),
+        0
+    );
+}
+
+#[test]
+fn assert_not_borrowed_ok() {
+    with_cryo(&42, |cryo| {
+        cryo.assert_not_borrowed();
+        drop(cryo.borrow());
+        cryo.assert_not_borrowed();
+    });
+}
+
+#[test]
+#[should_panic(expected = "`Cryo` is still borrowed")]
+fn assert_not_borrowed_fail() {
+    with_cryo(&42, |cryo| {
+        let _borrow = cryo.borrow();
+        cryo.assert_not_borrowed();
+    });
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn shared_cryo_ref_drop_waits_for_last_clone() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    static DROPPED: AtomicBool = AtomicBool::new(false);
+
+    let cell = 42;
+    with_cryo((&cell, lock_ty::<SyncLock>()), |cryo| {
+        let shared = cryo.borrow().into_shared();
+        let clones: Vec<_> = (0..3).map(|_| shared.clone()).collect();
+        assert_eq!(SharedCryoRef::strong_count(&shared), 4);
+        drop(shared);
+
+        spawn(move || {
+            sleep(Duration::from_millis(50));
+            assert!(clones.iter().all(|x| **x == 42));
+            DROPPED.store(true, Ordering::Relaxed);
+            drop(clones);
+        });
+    });
+    assert!(DROPPED.load(Ordering::Relaxed));
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn shared_cryo_ref_try_unwrap() {
+    with_cryo(&42, |cryo| {
+        let shared = cryo.borrow().into_shared();
+        let shared2 = shared.clone();
+        let shared = SharedCryoRef::try_unwrap(shared).unwrap_err();
+        drop(shared2);
+        let borrow: CryoRef<i32, _> = SharedCryoRef::try_unwrap(shared).unwrap();
+        assert_eq!(*borrow, 42);
+    });
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn weak_cryo_ref_graph_cycle() {
+    use std::cell::RefCell;
+
+    struct Node {
+        value: u32,
+        edges: RefCell<Vec<WeakCryoRef<Node, LocalLock>>>,
+    }
+
+    let nodes: Vec<Node> = (0..2)
+        .map(|value| Node {
+            value,
+            edges: RefCell::new(Vec::new()),
+        })
+        .collect();
+
+    // If the edges were `SharedCryoRef`s, the shared lock would still be held
+    // when the `Cryo` is dropped, and `LocalLock` would panic
+    with_cryo(&nodes, |cryo| {
+        let roots: Vec<_> = (0..2)
+            .map(|i| CryoRef::map(cryo.borrow(), |nodes| &nodes[i]).into_shared())
+            .collect();
+        roots[0]
+            .edges
+            .borrow_mut()
+            .push(SharedCryoRef::downgrade(&roots[1]));
+        roots[1]
+            .edges
+            .borrow_mut()
+            .push(SharedCryoRef::downgrade(&roots[0]));
+
+        // Walk the cycle 0 -> 1 -> 0
+        let next = WeakCryoRef::upgrade_all(&roots[0].edges.borrow()).unwrap();
+        assert_eq!(next[0].value, 1);
+        let back = WeakCryoRef::upgrade_all(&next[0].edges.borrow()).unwrap();
+        assert_eq!(back[0].value, 0);
+        drop((next, back));
+
+        drop(roots);
+        assert!(!cryo.would_block_on_seal());
+    });
+
+    // The nodes outlive the `Cryo`, and their edges have expired
+    assert!(WeakCryoRef::upgrade_all(&nodes[0].edges.borrow()).is_none());
+}
+
+#[test]
+fn would_block_on_seal() {
+    with_cryo(&42, |cryo| {
+        assert!(!cryo.would_block_on_seal());
+        let borrow = cryo.borrow();
+        assert!(cryo.would_block_on_seal());
+        drop(borrow);
+        assert!(!cryo.would_block_on_seal());
+    });
+}
+
+#[test]
+fn by_address_hash_set() {
+    use std::collections::HashSet;
+    let cells = [7, 7, 7];
+    with_cryo(&cells[0], |c0| {
+        with_cryo(&cells[1], |c1| {
+            let b0 = c0.borrow();
+            let mut set = HashSet::new();
+            assert!(set.insert(b0.clone().by_address()));
+            // Clones point to the same cell
+            assert!(!set.insert(b0.clone().by_address()));
+            assert!(!set.insert(c0.borrow().by_address()));
+            // Equal values in distinct cells don't collide
+            assert!(set.insert(c1.borrow().by_address()));
+            assert_eq!(set.len(), 2);
+        });
+    });
+}
+
+#[test]
+fn by_address_projection() {
+    let cell = String::from("x");
+    with_cryo(&cell, |cryo| {
+        // Projections from the same cell compare equal
+        let a: CryoRef<str, _> = cryo.borrow().deref_into();
+        let b: CryoRef<str, _> = cryo.borrow().deref_into();
+        assert_eq!(a.by_address(), b.by_address());
+    });
+}
+
+#[test]
+fn with_cryo_pin_ref() {
+    use std::marker::PhantomPinned;
+    struct NotUnpin(u32, PhantomPinned);
+
+    let pinned = Box::pin(NotUnpin(42, PhantomPinned));
+    let addr: *const NotUnpin = &*pinned;
+    with_cryo(pinned.as_ref(), |cryo| {
+        let borrow = cryo.borrow();
+        assert_eq!(borrow.0, 42);
+        assert_eq!(&*borrow as *const NotUnpin, addr);
+    });
+    with_cryo_pin_box(&pinned, |cryo| {
+        let borrows = (cryo.borrow(), cryo.borrow());
+        assert_eq!(borrows.0 .0 + borrows.1 .0, 84);
+    });
+    assert_eq!(&*pinned as *const NotUnpin, addr);
+}
+
+#[test]
+fn sort_cryos() {
+    let cells = [30u32, 10, 20];
+    let mut cryos: Vec<_> = cells
+        .iter()
+        .map(|x| Box::pin(unsafe { Cryo::<_, LocalLock>::new(x) }))
+        .collect();
+    cryos.sort();
+    let sorted: Vec<u32> = cryos.iter().map(|c| *c.as_ref().borrow()).collect();
+    assert_eq!(sorted, [10, 20, 30]);
+    assert!(cryos[0] < cryos[1]);
+    assert_eq!(*cryos[0], *cryos[0]);
+}
+
+#[test]
+fn map_field() {
+    let cell = (String::from("a"), String::from("b"));
+    with_cryo(&cell, |cryo| {
+        let second = CryoRef::map(cryo.borrow(), |x| x.1.as_str());
+        assert_eq!(&*second, "b");
+        // The shared lock is kept
+        assert!(cryo.would_block_on_seal());
+        // `Option::map` etc. aren't shadowed
+        let opt = CryoRef::map(cryo.borrow(), |_| &Some(1));
+        assert_eq!(opt.map(|x| x + 1), Some(2));
+    });
+}
+
+#[test]
+fn try_map_ok() {
+    with_cryo(&vec![1, 2, 3], |cryo| {
+        let borrow: CryoRef<i32, _> =
+            CryoRef::try_map(cryo.borrow(), |v| v.first().ok_or(())).unwrap();
+        assert_eq!(*borrow, 1);
+        assert_eq!(&*borrow as *const i32, cryo.get().as_ptr());
+    });
+}
+
+#[test]
+fn try_map_err_returns_original() {
+    with_cryo(&vec![1, 2, 3], |cryo| {
+        let original = cryo.borrow();
+        let addr: *const Vec<i32> = &*original;
+        let (original, e) = CryoRef::try_map(original, |v| v.get(10).ok_or(v.len())).unwrap_err();
+        assert_eq!(e, 3);
+        assert_eq!(&*original as *const Vec<i32>, addr);
+        assert_eq!(*original, [1, 2, 3]);
+    });
+}
+
+cryo_transparent! {
+    #[derive(Debug, PartialEq)]
+    struct UserId(u64);
+}
+
+cryo_transparent! {
+    struct Name(pub String);
+}
+
+#[test]
+fn peel_transparent() {
+    with_cryo(&UserId(42), |cryo| {
+        let id = cryo.borrow();
+        let inner: CryoRef<u64, _> = id.clone().peel_transparent();
+        assert_eq!(*inner, 42);
+        assert_eq!(*id, UserId(42));
+        assert!(std::ptr::eq(
+            CryoRef::as_ptr(&inner),
+            CryoRef::as_ptr(&id).cast()
+        ));
+    });
+}
+
+#[test]
+fn peel_transparent_then_deref() {
+    with_cryo(&Name("Alice".to_owned()), |cryo| {
+        let name: CryoRef<str, _> = cryo.borrow().peel_transparent().deref_into();
+        assert_eq!(&*name, "Alice");
+    });
+}
+
+#[test]
+fn transparent_unsafe_cast() {
+    #[repr(transparent)]
+    struct Wrapper(u32);
+
+    with_cryo(&Wrapper(7), |cryo| {
+        let inner: CryoRef<u32, _> = unsafe { cryo.borrow().transparent() };
+        assert_eq!(*inner, 7);
+    });
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "`Cryo` was dropped while 2 read guard(s) were outstanding")]
+fn drop_with_leaked_borrows_message() {
+    with_cryo(&42, |cryo| {
+        std::mem::forget(cryo.borrow());
+        std::mem::forget(cryo.borrow());
+    });
+}
+
+#[test]
+fn cryo_waker_in_custom_future() {
+    use std::{
+        future::Future,
+        pin::Pin,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, Wake, Waker},
+    };
+
+    struct SumFuture {
+        data: CryoWaker<[u32]>,
+        ready: Arc<AtomicBool>,
+    }
+
+    impl Future for SumFuture {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+            if self.ready.load(Ordering::Acquire) {
+                Poll::Ready(self.data.guard().iter().sum())
+            } else {
+                self.data.register(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+
+    struct CountingWaker(AtomicBool);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn assert_send_static<T: Send + 'static>(x: T) -> T {
+        x
+    }
+
+    let cells = [1, 2, 3];
+    with_cryo((&cells[..], lock_ty::<SyncLock>()), |cryo| {
+        let ready = Arc::new(AtomicBool::new(false));
+        let mut fut = assert_send_static(SumFuture {
+            data: CryoWaker::new(cryo.borrow()),
+            ready: Arc::clone(&ready),
+        });
+
+        let woken = Arc::new(CountingWaker(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&woken));
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Pending);
+        ready.store(true, Ordering::Release);
+        fut.data.wake();
+        assert!(woken.0.load(Ordering::Relaxed));
+
+        // Poll it on another thread
+        let output = spawn(move || {
+            let waker = futures::task::noop_waker();
+            Pin::new(&mut fut).poll(&mut Context::from_waker(&waker))
+        })
+        .join()
+        .unwrap();
+        assert_eq!(output, Poll::Ready(6));
+    });
+}
+
+#[cfg(feature = "lock_api")]
+#[test]
+fn with_cryo_mutex_guard_read() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// A minimal stand-in for `parking_lot::RawMutex`
+    struct SpinMutex(AtomicBool);
+
+    unsafe impl lock_api::RawMutex for SpinMutex {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INIT: Self = Self(AtomicBool::new(false));
+        type GuardMarker = lock_api::GuardSend;
+
+        fn lock(&self) {
+            while !self.try_lock() {
+                std::hint::spin_loop();
+            }
+        }
+
+        fn try_lock(&self) -> bool {
+            !self.0.swap(true, Ordering::Acquire)
+        }
+
+        unsafe fn unlock(&self) {
+            self.0.store(false, Ordering::Release);
+        }
+    }
+
+    let mutex = lock_api::Mutex::<SpinMutex, _>::new(vec![1, 2, 3]);
+    let mut guard = mutex.lock();
+    let sum = with_cryo_mutex_guard(&mut guard, |cryo| {
+        let borrow: CryoRef<Vec<u32>, _> = cryo.borrow();
+        assert!(mutex.try_lock().is_none());
+        borrow.iter().sum::<u32>()
+    });
+    assert_eq!(sum, 6);
+    drop(guard);
+    assert!(mutex.try_lock().is_some());
+}
+
+#[test]
+fn cryo_ref_cmp_value() {
+    use std::cmp::Ordering;
+
+    with_cryo(&42, |cryo| {
+        let borrow: CryoRef<i32, _> = cryo.borrow();
+        assert!(borrow == 42);
+        assert!(borrow != 41);
+        assert!(borrow < 43);
+        assert!(borrow > 41);
+        assert_eq!(borrow.partial_cmp(&42), Some(Ordering::Equal));
+
+        // Reverse order
+        assert!(42 == borrow);
+        assert!(43 > borrow);
+        assert_eq!(41.partial_cmp(&borrow), Some(Ordering::Less));
+    });
+
+    with_cryo("abc", |cryo| {
+        let borrow = cryo.borrow();
+        assert!(*"abc" == borrow);
+        assert!(borrow < *"abd");
+    });
+}
+
+#[test]
+fn with_cryo_cancellable_signal() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static NUM_EXITED: AtomicUsize = AtomicUsize::new(0);
+
+    let data = vec![1, 2, 3];
+    with_cryo_cancellable(&data, |borrow, cancelled| {
+        for _ in 0..4 {
+            let (borrow, cancelled) = (borrow.clone(), cancelled.clone());
+            spawn(move || {
+                while !cancelled.load(Ordering::Acquire) {
+                    assert_eq!(borrow.iter().sum::<i32>(), 6);
+                    sleep(Duration::from_millis(1));
+                }
+                drop(borrow);
+                NUM_EXITED.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+        sleep(Duration::from_millis(20));
+        cancelled.store(true, Ordering::Release);
+    });
+    // The `Cryo` waited for the workers to drop their `CryoRef`s
+    assert_eq!(NUM_EXITED.load(Ordering::Relaxed), 4);
+}
+
+#[test]
+fn with_cryo_cancellable_panic() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    static EXITED: AtomicBool = AtomicBool::new(false);
+
+    let data = 42;
+    let result = std::panic::catch_unwind(|| {
+        with_cryo_cancellable(&data, |borrow, cancelled| {
+            spawn(move || {
+                while !cancelled.load(Ordering::Acquire) {
+                    assert_eq!(*borrow, 42);
+                    sleep(Duration::from_millis(1));
+                }
+                drop(borrow);
+                EXITED.store(true, Ordering::Relaxed);
+            });
+            panic!("scope failed");
+        })
+    });
+    assert!(result.is_err());
+    assert!(EXITED.load(Ordering::Relaxed));
+}
+
+#[test]
+fn cryo_ref_filter() {
+    with_cryo(&7, |cryo| {
+        let num_shared_locks = || cryo.raw_lock().num_shared_locks();
+
+        let kept = CryoRef::filter(cryo.borrow(), |x| *x == 7).unwrap();
+        assert_eq!(*kept, 7);
+        assert_eq!(num_shared_locks(), Some(1));
+
+        let rejected = CryoRef::filter(kept.clone(), |x| *x != 7);
+        assert!(rejected.is_none());
+        assert_eq!(num_shared_locks(), Some(1));
+
+        drop(kept);
+        assert_eq!(num_shared_locks(), Some(0));
+    });
+}
+
+#[test]
+fn cryo_ref_filter_option_referent() {
+    // `Option::filter` on the referent isn't shadowed
+    with_cryo(&Some(3), |cryo| {
+        let borrow = cryo.borrow();
+        assert_eq!(borrow.filter(|x| *x > 5), None);
+        assert!(CryoRef::filter(borrow, Option::is_some).is_some());
+    });
+}
+
+#[test]
+fn defer_release_after_unlock() {
+    use std::cell::Cell;
+
+    let released = Cell::new(false);
+    with_cryo(&[1, 2], |cryo| {
+        let borrow = cryo.borrow().defer_release(|| {
+            // The lock is fully released by the time this runs
+            assert_eq!(cryo.raw_lock().num_shared_locks(), Some(0));
+            assert!(!cryo.would_block_on_seal());
+            released.set(true);
+        });
+        assert_eq!(borrow[1], 2);
+        assert_eq!(cryo.raw_lock().num_shared_locks(), Some(1));
+        assert!(!released.get());
+        drop(borrow);
+        assert!(released.get());
+    });
+}
+
+#[test]
+fn defer_release_with_other_borrows() {
+    use std::cell::Cell;
+
+    let num_left = Cell::new(None);
+    with_cryo(&0, |cryo| {
+        let other = cryo.borrow();
+        drop(
+            cryo.borrow()
+                .defer_release(|| num_left.set(cryo.raw_lock().num_shared_locks())),
+        );
+        drop(other);
+    });
+    assert_eq!(num_left.get(), Some(1));
+}
+
+#[test]
+fn with_cryo_project_field() {
+    use std::{cell::RefCell, rc::Rc};
+
+    struct Owner {
+        name: String,
+        values: Vec<u32>,
+        log: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl Drop for Owner {
+        fn drop(&mut self) {
+            self.log.borrow_mut().push("owner dropped");
+        }
+    }
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let owner = Owner {
+        name: "graph".to_owned(),
+        values: vec![4, 5, 6],
+        log: Rc::clone(&log),
+    };
+
+    let sum = with_cryo_project(
+        owner,
+        |owner| &owner.values[1..],
+        |cryo| {
+            let borrow: CryoRef<[u32], _> = cryo.borrow();
+            let cloned = borrow.clone();
+            log.borrow_mut().push("in scope");
+            borrow.iter().chain(cloned.iter()).sum::<u32>()
+        },
+    );
+    assert_eq!(sum, 22);
+    assert_eq!(*log.borrow(), ["in scope", "owner dropped"]);
+
+    let owner = Owner {
+        name: "other".to_owned(),
+        values: Vec::new(),
+        log: Rc::clone(&log),
+    };
+    let name = with_cryo_project(
+        owner,
+        |owner| &owner.name,
+        |cryo| cryo.borrow().to_uppercase(),
+    );
+    assert_eq!(name, "OTHER");
+}
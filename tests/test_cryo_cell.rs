@@ -0,0 +1,224 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+#![warn(rust_2018_idioms)]
+
+use cryo::*;
+use pin_utils::pin_mut;
+
+use std::{
+    sync::{mpsc, Arc},
+    thread::spawn,
+};
+
+#[test]
+fn read_write() {
+    let cell: CryoCell<_, LocalLock> = CryoCell::new(42);
+    pin_mut!(cell);
+    let cell = cell.as_ref();
+
+    *cell.write() += 1;
+    let borrow = cell.read();
+    assert_eq!(*borrow, 43);
+    assert!(cell.try_write().is_none());
+    assert!(cell.try_read().is_some());
+    drop(borrow);
+    assert!(cell.try_write().is_some());
+}
+
+#[test]
+fn get_mut() {
+    let mut cell: CryoCell<_, LocalLock> = CryoCell::new(vec![1]);
+    cell.get_mut().push(2);
+    assert_eq!(*cell.get_mut(), [1, 2]);
+}
+
+#[test]
+fn into_inner() {
+    let cell: CryoCell<_, SyncLock> = CryoCell::new(String::from("hello"));
+    assert_eq!(cell.into_inner(), "hello");
+}
+
+#[test]
+fn into_inner_drops_once() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    let cell: CryoCell<_, LocalLock> = CryoCell::new(Rc::clone(&rc));
+    assert_eq!(Rc::strong_count(&rc), 2);
+    let inner = cell.into_inner();
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(inner);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+#[should_panic]
+fn drop_while_borrowed() {
+    let cell: CryoCell<_, LocalLock> = CryoCell::new(42);
+    pin_mut!(cell);
+    std::mem::forget(cell.as_ref().read());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "`CryoCell` was dropped while 1 read guard(s) were outstanding")]
+fn drop_while_borrowed_message() {
+    let cell: CryoCell<_, LocalLock> = CryoCell::new(42);
+    pin_mut!(cell);
+    std::mem::forget(cell.as_ref().read());
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn read_arc_detached() {
+    let (send, recv) = mpsc::channel::<()>();
+    let (done_send, done_recv) = mpsc::channel();
+
+    {
+        let cell: std::pin::Pin<Arc<CryoCell<_, AtomicLock>>> =
+            Arc::pin(CryoCell::new(String::from("hello")));
+        let borrow = cell.read_arc();
+        assert!(cell.as_ref().try_write().is_none());
+
+        // The thread is not joined in this scope
+        spawn(move || {
+            recv.recv().unwrap();
+            done_send.send(borrow.clone()).unwrap();
+            assert_eq!(*borrow, "hello");
+        });
+    }
+
+    // The `CryoCell` is kept alive by `ArcCryoRef`
+    send.send(()).unwrap();
+    let borrow = done_recv.recv().unwrap();
+    assert_eq!(*borrow, "hello");
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn read_arc_releases_lock() {
+    let cell: std::pin::Pin<Arc<CryoCell<_, LocalLock>>> = Arc::pin(CryoCell::new(1));
+    let borrow = cell.read_arc();
+    let borrow2 = borrow.clone();
+    assert_eq!(*borrow2, 1);
+    drop(borrow);
+    assert!(cell.as_ref().try_write().is_none());
+    drop(borrow2);
+    *cell.as_ref().write() = 2;
+    assert_eq!(*cell.read_arc(), 2);
+}
+
+#[test]
+fn default() {
+    #[derive(Default)]
+    struct Holder {
+        cell: CryoCell<Vec<u8>, LocalLock>,
+    }
+
+    let holder = Holder::default();
+    pin_mut!(holder);
+    // Safety: `cell` is structurally pinned
+    let cell = unsafe { holder.as_ref().map_unchecked(|h| &h.cell) };
+    assert!(cell.read().is_empty());
+    cell.write().push(1);
+    assert_eq!(*cell.read(), [1]);
+}
+
+#[test]
+fn debug() {
+    let cell: CryoCell<_, LocalLock> = CryoCell::new(42);
+    pin_mut!(cell);
+    assert_eq!(format!("{:?}", cell), "CryoCell { data: 42 }");
+    let _guard = cell.as_ref().write();
+    assert_eq!(format!("{:?}", cell), "CryoCell { data: <locked> }");
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn arc_cryo_drop_orders() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+
+    // Handles first, then borrows
+    let a: ArcCryo<_, LocalLock> = ArcCryo::new(Rc::clone(&rc));
+    let b = a.clone();
+    let (ra, rb) = (a.borrow(), b.borrow());
+    drop(a);
+    drop(b);
+    assert!(Rc::ptr_eq(&ra, &rb));
+    drop(ra);
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(rb);
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    // Borrows first, then handles
+    let a: ArcCryo<_, LocalLock> = ArcCryo::new(Rc::clone(&rc));
+    let b = a.clone();
+    let (ra, rb) = (b.borrow(), a.borrow());
+    drop(rb);
+    drop(ra);
+    assert!(a.as_cell().try_write().is_some());
+    drop(b);
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(a);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn arc_cryo_threads() {
+    let cryo: ArcCryo<_, AtomicLock> = ArcCryo::new(vec![1, 2, 3]);
+    let handles: Vec<_> = (0..3)
+        .map(|i| {
+            let cryo = cryo.clone();
+            spawn(move || cryo.borrow()[i])
+        })
+        .collect();
+    let borrow = cryo.borrow();
+    drop(cryo);
+    let sum: i32 = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    assert_eq!(sum, 6);
+    assert_eq!(borrow.len(), 3);
+}
+
+#[test]
+fn get_set_local_lock() {
+    let cell = CryoCell::<_, LocalLock>::new((1, 'a'));
+    assert_eq!(cell.get(), (1, 'a'));
+    cell.set((2, 'b'));
+    assert_eq!(cell.get(), (2, 'b'));
+
+    pin_mut!(cell);
+    let borrow = cell.as_ref().read();
+    assert_eq!(cell.get(), (2, 'b'));
+    drop(borrow);
+    cell.as_ref().set((3, 'c'));
+    assert_eq!(*cell.as_ref().read(), (3, 'c'));
+}
+
+#[test]
+fn get_set_sync_lock() {
+    let cell = Arc::pin(CryoCell::<_, SyncLock>::new(1u64));
+    cell.set(2);
+    assert_eq!(cell.get(), 2);
+
+    // `get` waits for the guard held by another thread
+    let mut guard = cell.as_ref().write();
+    let (send, recv) = mpsc::channel();
+    let thread = spawn(move || {
+        recv.recv().unwrap();
+        *guard += 10;
+    });
+    send.send(()).unwrap();
+    let value = cell.get();
+    assert_eq!(value, 12);
+    cell.set(value + 1);
+    thread.join().unwrap();
+    assert_eq!(*cell.as_ref().read(), 13);
+}
@@ -102,3 +102,978 @@ fn block_by_exclusive_access() {
         assert_eq!(std::mem::replace(&mut *cryo_mut.write(), 100), 72);
     });
 }
+
+#[test]
+fn read_into_iter() {
+    with_cryo(&mut vec![1u8, 2, 3], |cryo_mut| {
+        let borrow = cryo_mut.read();
+        let mut sum = 0;
+        for x in &borrow {
+            sum += *x;
+        }
+        assert_eq!(sum, 6);
+        assert_eq!((&borrow).into_iter().count(), 3);
+    });
+}
+
+#[test]
+fn write_into_iter() {
+    let mut cell = vec![1u8, 2, 3];
+    with_cryo(&mut cell, |cryo_mut| {
+        let mut borrow = cryo_mut.write();
+        for x in &mut borrow {
+            *x *= 2;
+        }
+        assert_eq!(
+            (&borrow).into_iter().copied().collect::<Vec<_>>(),
+            [2, 4, 6]
+        );
+    });
+    assert_eq!(cell, [2, 4, 6]);
+}
+
+#[test]
+fn read_transpose() {
+    with_cryo(&mut Some(42), |cryo_mut| {
+        let borrow = cryo_mut.read().transpose().unwrap();
+        assert_eq!(*borrow, 42);
+        assert!(cryo_mut.try_write().is_none());
+    });
+}
+
+#[test]
+fn read_transpose_none_releases_lock() {
+    with_cryo(&mut None::<i32>, |cryo_mut| {
+        assert!(cryo_mut.read().transpose().is_none());
+        assert!(cryo_mut.try_write().is_some());
+    });
+}
+
+#[test]
+fn write_transpose() {
+    let mut cell = Some(42);
+    with_cryo(&mut cell, |cryo_mut| {
+        let mut borrow = cryo_mut.write().transpose().unwrap();
+        *borrow = 56;
+        assert!(cryo_mut.try_read().is_none());
+    });
+    assert_eq!(cell, Some(56));
+}
+
+#[test]
+fn write_transpose_none_releases_lock() {
+    with_cryo(&mut None::<i32>, |cryo_mut| {
+        assert!(cryo_mut.write().transpose().is_none());
+        assert!(cryo_mut.try_read().is_some());
+    });
+}
+
+#[test]
+fn with_field_mut() {
+    struct Inner {
+        value: u32,
+    }
+    struct Outer {
+        inner: Inner,
+        other: u32,
+    }
+
+    let mut cell = Outer {
+        inner: Inner { value: 1 },
+        other: 2,
+    };
+    with_cryo(&mut cell, |cryo_mut| {
+        let old =
+            cryo_mut.with_field_mut(|x| &mut x.inner.value, |value| std::mem::replace(value, 42));
+        assert_eq!(old, 1);
+        assert!(cryo_mut.try_write().is_some());
+    });
+    assert_eq!(cell.inner.value, 42);
+    assert_eq!(cell.other, 2);
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn write_sink_stream() {
+    use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
+
+    let (mut send, mut recv) = mpsc::channel::<u32>(4);
+    with_cryo((&mut send, lock_ty::<SyncLock>()), |cryo_mut| {
+        let mut borrow = cryo_mut.write();
+        spawn(move || {
+            block_on(async {
+                borrow.send(1).await.unwrap();
+                borrow.send(2).await.unwrap();
+            })
+        });
+    });
+    drop(send);
+
+    with_cryo(&mut recv, |cryo_mut| {
+        let mut borrow = cryo_mut.write();
+        let items: Vec<u32> = block_on((&mut borrow).collect());
+        assert_eq!(items, [1, 2]);
+    });
+}
+
+#[test]
+fn condvar_lock_block_by_exclusive_access() {
+    with_cryo((&mut 42, lock_ty::<CondvarLock>()), |cryo_mut| {
+        let mut borrow = cryo_mut.write();
+        spawn(move || {
+            sleep(Duration::from_millis(50));
+            *borrow = 56;
+        });
+        std::thread::scope(|s| {
+            s.spawn(|| assert_eq!(*cryo_mut.read(), 56));
+        });
+    });
+}
+
+#[test]
+fn transact() {
+    with_cryo(&mut 1, |cryo_mut| {
+        assert_eq!(
+            cryo_mut.transact(|x| *x == 1, |x| std::mem::replace(x, 2)),
+            Some(1)
+        );
+        assert_eq!(
+            cryo_mut.transact(|x| *x == 1, |x| std::mem::replace(x, 3)),
+            None
+        );
+        assert_eq!(*cryo_mut.read(), 2);
+    });
+}
+
+#[test]
+fn transact_concurrent() {
+    let mut cell = 0;
+    with_cryo((&mut cell, lock_ty::<CondvarLock>()), |cryo_mut| {
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for _ in 0..200 {
+                        // The check and the increment must not be interleaved
+                        // with other threads' for the count to stop at 800
+                        cryo_mut.transact(|x| *x < 800, |x| *x += 1);
+                    }
+                });
+            }
+        });
+    });
+    assert_eq!(cell, 800);
+}
+
+#[test]
+fn guard_future_releases_on_completion() {
+    use futures::{future::poll_fn, task::noop_waker};
+    use std::{
+        future::Future,
+        task::{Context, Poll},
+    };
+
+    with_cryo(&mut 42, |cryo_mut| {
+        let mut ready = false;
+        let fut = cryo_mut.read().guard_future(poll_fn(move |_| {
+            if ready {
+                Poll::Ready(1)
+            } else {
+                ready = true;
+                Poll::Pending
+            }
+        }));
+        pin_mut!(fut);
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+        assert!(fut.guard().is_some());
+        assert!(cryo_mut.try_write().is_none());
+
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(1));
+        assert!(fut.guard().is_none());
+        // `fut` is still alive, but the lock has been released
+        assert!(cryo_mut.try_write().is_some());
+    });
+}
+
+#[test]
+fn flatten_keeps_lock() {
+    with_cryo(&mut Box::new(42), |cryo_mut| {
+        let flat = cryo_mut.read().flatten();
+        assert_eq!(*flat, 42);
+        assert!(cryo_mut.try_write().is_none());
+        drop(flat);
+        assert!(cryo_mut.try_write().is_some());
+    });
+}
+
+#[test]
+fn with_cryo_mut_as_sync_lock() {
+    let mut cell = 42;
+    with_cryo_mut_as::<SyncLock, _, _>(&mut cell, |cryo_mut| {
+        let mut borrow = cryo_mut.write();
+        spawn(move || *borrow += 1);
+    });
+    assert_eq!(cell, 43);
+}
+
+#[test]
+fn with_entry_hash_map() {
+    use std::collections::{hash_map::Entry, HashMap};
+    let mut map = HashMap::new();
+    with_cryo(&mut map, |cryo_mut| {
+        cryo_mut.with_entry("a", |e| *e.or_insert(1) += 1);
+        cryo_mut.with_entry("b", |e| {
+            e.or_insert(10);
+        });
+        let was_vacant = cryo_mut.with_entry("a", |e| match e {
+            Entry::Occupied(mut e) => {
+                *e.get_mut() *= 10;
+                false
+            }
+            Entry::Vacant(_) => true,
+        });
+        assert!(!was_vacant);
+        assert!(cryo_mut.try_write().is_some());
+    });
+    assert_eq!(map["a"], 20);
+    assert_eq!(map["b"], 10);
+}
+
+#[test]
+fn with_entry_btree_map() {
+    use std::collections::BTreeMap;
+    let mut map = BTreeMap::new();
+    with_cryo((&mut map, lock_ty::<SyncLock>()), |cryo_mut| {
+        for &k in &[3, 1, 3, 2, 3] {
+            cryo_mut.with_entry(k, |e| *e.or_insert(0) += 1);
+        }
+    });
+    assert_eq!(
+        map.into_iter().collect::<Vec<_>>(),
+        [(1, 1), (2, 1), (3, 3)]
+    );
+}
+
+#[test]
+fn with_entry_borrowed_keys() {
+    use std::collections::HashMap;
+    let words = String::from("b a b");
+    let mut map: HashMap<&str, usize> = HashMap::new();
+    with_cryo(&mut map, |cryo_mut| {
+        for word in words.split(' ') {
+            cryo_mut.with_entry(word, |e| *e.or_insert(0) += 1);
+        }
+    });
+    assert_eq!(map["a"], 1);
+    assert_eq!(map["b"], 2);
+}
+
+#[test]
+fn deref_into_keeps_lock() {
+    with_cryo(&mut vec![1u8, 2], |cryo_mut| {
+        let borrow: CryoMutReadGuard<[u8], _> = cryo_mut.read().deref_into();
+        assert_eq!(*borrow, [1, 2]);
+        assert!(cryo_mut.try_write().is_none());
+        drop(borrow);
+        assert!(cryo_mut.try_write().is_some());
+    });
+}
+
+#[test]
+fn with_cryo_mut_box_string() {
+    let (x, len) = with_cryo_mut_box(Box::new(String::from("hello")), |cryo_mut| {
+        let mut borrow = cryo_mut.write();
+        borrow.push_str(", world");
+        borrow.len()
+    });
+    assert_eq!(*x, "hello, world");
+    assert_eq!(len, 12);
+}
+
+#[test]
+fn read_then() {
+    with_cryo(&mut vec![3, 1, 2], |cryo_mut| {
+        let (guard, len) = cryo_mut.read_then(|v| v.len());
+        assert_eq!(len, 3);
+        assert_eq!(guard[len - 1], 2);
+        // The lock is still held
+        assert!(cryo_mut.try_write().is_none());
+        drop(guard);
+        assert!(cryo_mut.try_write().is_some());
+    });
+}
+
+#[test]
+fn write_then() {
+    with_cryo(&mut vec![3, 1, 2], |cryo_mut| {
+        let (mut guard, old_len) = cryo_mut.write_then(|v| {
+            let len = v.len();
+            v.sort();
+            len
+        });
+        assert_eq!(old_len, 3);
+        guard.push(4);
+        assert!(cryo_mut.try_read().is_none());
+        drop(guard);
+        assert_eq!(*cryo_mut.read(), [1, 2, 3, 4]);
+    });
+}
+
+#[test]
+fn with_cryo_refcell_mutate() {
+    use std::cell::RefCell;
+    let cell = RefCell::new(String::from("a"));
+    let len = with_cryo_refcell(&cell, |cryo_mut| {
+        let mut borrow = cryo_mut.write();
+        let closure: Box<dyn FnOnce()> = Box::new(move || borrow.push('b'));
+        closure();
+        // The `RefCell` is borrowed for the whole scope
+        assert!(cell.try_borrow().is_err());
+        cryo_mut.read().len()
+    });
+    assert_eq!(len, 2);
+    assert_eq!(*cell.borrow(), "ab");
+}
+
+#[test]
+#[should_panic]
+fn with_cryo_refcell_already_borrowed() {
+    use std::cell::RefCell;
+    let cell = RefCell::new(0);
+    let _borrow = cell.borrow();
+    with_cryo_refcell(&cell, |_| {});
+}
+
+#[test]
+fn set() {
+    /// Records that it has been dropped
+    struct DropCheck<'a>(&'a std::cell::Cell<bool>, Vec<u8>);
+    impl Drop for DropCheck<'_> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let dropped = std::cell::Cell::new(false);
+    let mut cell = DropCheck(&dropped, vec![1]);
+    with_cryo(&mut cell, |cryo_mut| {
+        cryo_mut.set(DropCheck(&dropped, vec![2, 3]));
+        assert!(dropped.get());
+        assert_eq!(cryo_mut.read().1, [2, 3]);
+        assert!(cryo_mut.try_write().is_some());
+    });
+}
+
+#[test]
+fn replace() {
+    let mut cell = vec![1u8];
+    with_cryo(&mut cell, |cryo_mut| {
+        assert_eq!(cryo_mut.replace(vec![2]), [1]);
+    });
+    assert_eq!(cell, [2]);
+}
+
+#[test]
+fn take() {
+    let mut cell = vec![1u8, 2];
+    with_cryo(&mut cell, |cryo_mut| {
+        assert_eq!(cryo_mut.take(), [1, 2]);
+        assert!(cryo_mut.read().is_empty());
+    });
+    assert!(cell.is_empty());
+}
+
+#[test]
+fn replace_with() {
+    let mut cell = vec![1u8, 2];
+    with_cryo(&mut cell, |cryo_mut| {
+        let old = cryo_mut.replace_with(|v| v.iter().map(|x| x * 10).collect());
+        assert_eq!(old, [1, 2]);
+    });
+    assert_eq!(cell, [10, 20]);
+}
+
+#[test]
+fn replace_with_panic() {
+    let mut cell = vec![1u8];
+    with_cryo(&mut cell, |cryo_mut| {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            cryo_mut.replace_with(|v| {
+                v.push(2);
+                panic!()
+            })
+        }));
+        assert!(result.is_err());
+        // The lock has been released
+        assert_eq!(*cryo_mut.read(), [1, 2]);
+    });
+}
+
+#[test]
+fn watched() {
+    use std::cell::RefCell;
+    let log = RefCell::new(Vec::new());
+    let mut cell = 0;
+    with_cryo_watched(
+        &mut cell,
+        |x| log.borrow_mut().push(*x),
+        |cryo_mut| {
+            {
+                let mut guard = cryo_mut.write();
+                *guard += 1;
+                *guard += 1;
+            }
+            // Read locks don't call the function
+            assert_eq!(*cryo_mut.read(), 2);
+            *cryo_mut.try_write().unwrap() = 5;
+            assert_eq!(*log.borrow(), [2, 5]);
+        },
+    );
+    assert_eq!(cell, 5);
+}
+
+#[test]
+fn watched_sync_lock() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    let last = Arc::new(AtomicUsize::new(0));
+    let last2 = Arc::clone(&last);
+    let mut cell = 0usize;
+    let on_write = move |x: &usize| last2.store(*x, Ordering::Relaxed);
+    let cryo_mut = unsafe { WatchedCryoMut::<_, SyncLock, _>::new(&mut cell, on_write) };
+    pin_mut!(cryo_mut);
+    let mut guard = cryo_mut.as_ref().write();
+    spawn(move || *guard = 42).join().unwrap();
+    assert_eq!(last.load(Ordering::Relaxed), 42);
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn try_write_spins() {
+    with_cryo((&mut 0, lock_ty::<AtomicLock>()), |cryo_mut| {
+        let borrow = cryo_mut.read();
+        // Gives up after the given number of spins
+        assert!(cryo_mut.try_write_spins(0).is_none());
+        assert!(cryo_mut.try_write_spins(100).is_none());
+        drop(borrow);
+        *cryo_mut.try_write_spins(0).unwrap() = 1;
+    });
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn try_write_spins_contended() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    static RELEASED: AtomicBool = AtomicBool::new(false);
+    with_cryo((&mut 0, lock_ty::<AtomicLock>()), |cryo_mut| {
+        let borrow = cryo_mut.read();
+        let handle = spawn(move || {
+            drop(borrow);
+            RELEASED.store(true, Ordering::Release);
+        });
+        while !RELEASED.load(Ordering::Acquire) {
+            std::hint::spin_loop();
+        }
+        assert!(cryo_mut.try_write_spins(u32::MAX).is_some());
+        handle.join().unwrap();
+    });
+}
+
+#[test]
+fn compare_exchange_match() {
+    with_cryo(&mut String::from("a"), |cryo_mut| {
+        let old = cryo_mut.compare_exchange(&String::from("a"), String::from("b"));
+        assert_eq!(old, Ok(String::from("a")));
+        assert_eq!(*cryo_mut.read(), "b");
+    });
+}
+
+#[test]
+fn compare_exchange_mismatch() {
+    with_cryo(&mut String::from("a"), |cryo_mut| {
+        let new = cryo_mut.compare_exchange(&String::from("x"), String::from("b"));
+        assert_eq!(new, Err(String::from("b")));
+        assert_eq!(*cryo_mut.read(), "a");
+    });
+}
+
+#[test]
+fn unwind_safety() {
+    use std::panic::{RefUnwindSafe, UnwindSafe};
+    fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>() {}
+    assert_unwind_safe::<CryoMutReadGuard<u32, LocalLock>>();
+    #[cfg(feature = "atomic")]
+    assert_unwind_safe::<CryoMutReadGuard<u32, AtomicLock>>();
+
+    #[cfg(feature = "atomic")]
+    with_cryo((&mut 1, lock_ty::<AtomicLock>()), |cryo_mut| {
+        let mut guard = cryo_mut.write();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+            *guard = 2;
+            panic!("in the middle of a write");
+        }));
+        assert!(result.is_err());
+        assert_eq!(*cryo_mut.read(), 2);
+    });
+}
+
+#[test]
+fn read_guard_as_ptr() {
+    let mut cell = [1u32, 2];
+    let expected: *const [u32; 2] = &cell;
+    with_cryo(&mut cell, |cryo_mut| {
+        let borrow1 = cryo_mut.read();
+        let borrow2 = borrow1.clone();
+        assert_eq!(CryoRef::as_ptr(&borrow1), expected);
+        assert_eq!(CryoRef::as_ptr(&borrow2), expected);
+        assert_eq!(
+            CryoRef::as_non_null(&borrow1),
+            CryoRef::as_non_null(&borrow2)
+        );
+        assert_eq!(unsafe { *CryoRef::as_ptr(&borrow2) }, [1, 2]);
+        // Doesn't shadow the referent's methods
+        assert_eq!(borrow1.as_ptr(), expected as *const u32);
+    });
+}
+
+#[test]
+fn write_guard_as_mut_ptr() {
+    let mut cell = 1u32;
+    with_cryo(&mut cell, |cryo_mut| {
+        let mut borrow = cryo_mut.write();
+        unsafe { *CryoMutWriteGuard::as_mut_ptr(&mut borrow) = 2 };
+        unsafe { *CryoMutWriteGuard::as_non_null(&mut borrow).as_ptr() += 1 };
+        assert_eq!(*borrow, 3);
+    });
+    assert_eq!(cell, 3);
+}
+
+#[test]
+fn update() {
+    with_cryo(&mut vec![1], |cryo_mut| {
+        assert_eq!(
+            cryo_mut.update(|v| {
+                v.push(2);
+                v.len()
+            }),
+            2
+        );
+        assert_eq!(*cryo_mut.read(), [1, 2]);
+    });
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn try_helpers_atomic_lock_borrowed() {
+    with_cryo((&mut 1, lock_ty::<AtomicLock>()), |cryo_mut| {
+        let borrow = cryo_mut.read();
+        assert_eq!(cryo_mut.try_update(|_| unreachable!()), None::<()>);
+        assert_eq!(cryo_mut.try_set(2), Err(2));
+        assert_eq!(cryo_mut.try_replace(3), Err(3));
+        assert_eq!(*borrow, 1);
+        drop(borrow);
+
+        assert_eq!(cryo_mut.try_update(|x| *x * 10), Some(10));
+        assert_eq!(cryo_mut.try_set(2), Ok(()));
+        assert_eq!(cryo_mut.try_replace(3), Ok(2));
+        assert_eq!(*cryo_mut.read(), 3);
+    });
+}
+
+#[test]
+fn lock_both_opposite_orders() {
+    with_cryo((&mut 0u32, lock_ty::<CondvarLock>()), |a| {
+        with_cryo((&mut 0u32, lock_ty::<CondvarLock>()), |b| {
+            std::thread::scope(|s| {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        lock_both(a, b, |a, b| {
+                            *a += 1;
+                            *b += 1;
+                        });
+                    }
+                });
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        lock_both(b, a, |b, a| {
+                            *a += 1;
+                            *b += 1;
+                        });
+                    }
+                });
+            });
+            assert_eq!((*a.read(), *b.read()), (2000, 2000));
+        });
+    });
+}
+
+#[test]
+#[should_panic]
+fn lock_both_same_cell() {
+    with_cryo(&mut 0, |a| {
+        lock_both(a, a, |_, _| {});
+    });
+}
+
+#[test]
+fn lock_both_releases_on_panic() {
+    with_cryo(&mut 0, |a| {
+        with_cryo(&mut 0, |b| {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                lock_both(a, b, |_, _| panic!())
+            }));
+            assert!(result.is_err());
+            assert!(a.try_write().is_some());
+            assert!(b.try_write().is_some());
+        });
+    });
+}
+
+#[test]
+fn write_guard_maybe_uninit() {
+    use std::mem::MaybeUninit;
+    let mut cell = MaybeUninit::<String>::uninit();
+    with_cryo(&mut cell, |cryo_mut| {
+        let mut guard: CryoMutWriteGuard<String, _> = cryo_mut.write().write("hello".to_owned());
+        guard.push_str(", world");
+        // Still locked exclusively
+        assert!(cryo_mut.try_read().is_none());
+        drop(guard);
+
+        let guard = unsafe { cryo_mut.write().assume_init() };
+        assert_eq!(*guard, "hello, world");
+    });
+    assert_eq!(unsafe { cell.assume_init() }, "hello, world");
+}
+
+#[test]
+fn assert_not_borrowed_ok() {
+    with_cryo(&mut 42, |cryo_mut| {
+        cryo_mut.assert_not_borrowed();
+        drop(cryo_mut.write());
+        cryo_mut.assert_not_borrowed();
+    });
+}
+
+#[test]
+#[should_panic(expected = "`CryoMut` is still borrowed")]
+fn assert_not_borrowed_fail_read() {
+    with_cryo(&mut 42, |cryo_mut| {
+        let _borrow = cryo_mut.read();
+        cryo_mut.assert_not_borrowed();
+    });
+}
+
+#[test]
+#[should_panic(expected = "`CryoMut` is still borrowed")]
+fn assert_not_borrowed_fail_write() {
+    with_cryo(&mut 42, |cryo_mut| {
+        let _borrow = cryo_mut.write();
+        cryo_mut.assert_not_borrowed();
+    });
+}
+
+#[test]
+fn would_block_on_seal() {
+    with_cryo((&mut 42, lock_ty::<SyncLock>()), |cryo_mut| {
+        assert!(!cryo_mut.would_block_on_seal());
+        let borrow = cryo_mut.read();
+        assert!(cryo_mut.would_block_on_seal());
+        drop(borrow);
+        let borrow = cryo_mut.write();
+        assert!(cryo_mut.would_block_on_seal());
+        drop(borrow);
+        assert!(!cryo_mut.would_block_on_seal());
+    });
+}
+
+#[test]
+fn write_flushing() {
+    use std::io::{BufWriter, Write};
+    let mut writer = BufWriter::new(Vec::new());
+    with_cryo(&mut writer, |cryo_mut| {
+        let mut guard = cryo_mut.write_flushing();
+        guard.write_all(b"hello").unwrap();
+        assert!(guard.get_ref().is_empty());
+        drop(guard);
+        assert_eq!(cryo_mut.read().get_ref(), b"hello");
+
+        let mut guard = cryo_mut.write_flushing();
+        guard.write_all(b", world").unwrap();
+        guard.finish().unwrap();
+        assert_eq!(cryo_mut.read().get_ref(), b"hello, world");
+    });
+}
+
+#[test]
+fn write_flushing_error() {
+    use std::io::{self, Write};
+    struct FailingFlush(u32);
+    impl Write for FailingFlush {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            self.0 += 1;
+            Err(io::Error::other("flush failed"))
+        }
+    }
+
+    let mut cell = FailingFlush(0);
+    with_cryo(&mut cell, |cryo_mut| {
+        // Ignored on drop
+        drop(cryo_mut.write_flushing());
+        assert!(cryo_mut.write_flushing().finish().is_err());
+    });
+    assert_eq!(cell.0, 2);
+}
+
+#[test]
+fn write_flushing_panicking_flush() {
+    use std::io::{self, Write};
+    struct PanickingFlush;
+    impl Write for PanickingFlush {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            panic!("flush failed");
+        }
+    }
+
+    with_cryo(&mut PanickingFlush, |cryo_mut| {
+        let guard = cryo_mut.write_flushing();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(guard)));
+        assert!(result.is_err());
+        // The lock was released while unwinding
+        assert!(cryo_mut.try_write().is_some());
+    });
+}
+
+#[test]
+fn cmp_locked() {
+    with_cryo(&mut 2, |a| {
+        with_cryo(&mut 1, |b| {
+            assert!(*a > *b);
+            let guard = b.write();
+            // Locked is greater
+            assert!(*a < *b);
+            assert_eq!(Ord::cmp(&*b, &*b), std::cmp::Ordering::Equal);
+            assert_ne!(*a, *b);
+            drop(guard);
+            assert!(*a > *b);
+        });
+    });
+}
+
+#[test]
+fn slot_put_from_worker() {
+    let mut result = None;
+    with_cryo_slot::<SyncLock, _, _>(&mut result, |slot| {
+        let mut guard = slot.write();
+        let worker = std::thread::spawn(move || {
+            *guard = Some(String::from("done"));
+        });
+        worker.join().unwrap();
+        assert_eq!(slot.take().as_deref(), Some("done"));
+        assert_eq!(slot.take(), None);
+        slot.put(String::from("again"));
+    });
+    assert_eq!(result.as_deref(), Some("again"));
+}
+
+#[test]
+fn slot_put_scoped_worker() {
+    let mut result = None;
+    with_cryo_slot::<CondvarLock, _, _>(&mut result, |slot| {
+        std::thread::scope(|s| {
+            s.spawn(|| slot.put(42));
+        });
+        assert_eq!(*slot.read(), Some(42));
+        assert_eq!(slot.take(), Some(42));
+    });
+    assert_eq!(result, None);
+}
+
+#[test]
+fn poll_boxed_future_from_static_task() {
+    use std::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    let mut fut: Pin<Box<dyn Future<Output = u32> + Send>> = Box::pin(async {
+        YieldOnce(false).await;
+        YieldOnce(false).await;
+        7
+    });
+
+    with_cryo((&mut fut, lock_ty::<SyncLock>()), |cryo_mut| {
+        let mut guard = cryo_mut.write();
+        spawn(move || {
+            let waker = futures::task::noop_waker();
+            let mut cx = Context::from_waker(&waker);
+            let mut num_polls = 1;
+            while guard.poll_unpin_guard().poll(&mut cx).is_pending() {
+                num_polls += 1;
+            }
+            assert_eq!(num_polls, 3);
+        });
+    });
+}
+
+#[test]
+fn with_cryo_retry_after_contention() {
+    use std::ops::ControlFlow;
+
+    let mut cell = 1;
+    let mut stray_borrow = None;
+    let mut attempts = 0;
+    let result = with_cryo_retry(&mut cell, |cryo_mut| {
+        attempts += 1;
+        if attempts == 1 {
+            // Simulate contention with a guard held elsewhere
+            stray_borrow = Some(cryo_mut.read());
+        }
+
+        match cryo_mut.try_write() {
+            Some(mut guard) => {
+                *guard *= 10;
+                ControlFlow::Break(*guard)
+            }
+            None => {
+                stray_borrow = None;
+                ControlFlow::Continue(())
+            }
+        }
+    });
+    assert_eq!(result, 10);
+    assert_eq!(attempts, 2);
+    assert_eq!(cell, 10);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "`CryoMut` was dropped while 1 read guard(s) were outstanding")]
+fn drop_with_leaked_read_guard_message() {
+    with_cryo(&mut 42, |cryo_mut| {
+        std::mem::forget(cryo_mut.read());
+    });
+}
+
+#[test]
+fn fetch_update_applied() {
+    let mut cell = vec![1, 2];
+    with_cryo(&mut cell, |cryo_mut| {
+        let old = cryo_mut.fetch_update(|x| Some(x.iter().map(|i| i * 10).collect()));
+        assert_eq!(old, Ok(vec![1, 2]));
+    });
+    assert_eq!(cell, [10, 20]);
+}
+
+#[test]
+fn fetch_update_skipped() {
+    let mut cell = String::from("frozen");
+    with_cryo(&mut cell, |cryo_mut| {
+        let result = cryo_mut.fetch_update(|x| {
+            if x.is_empty() {
+                Some("new".into())
+            } else {
+                None
+            }
+        });
+        assert_eq!(result, Err(String::from("frozen")));
+    });
+    assert_eq!(cell, "frozen");
+}
+
+#[test]
+fn write_guard_option_methods() {
+    let mut cell: Option<String> = None;
+    with_cryo(&mut cell, |cryo_mut| {
+        let mut guard = cryo_mut.write();
+        // `Option`'s methods are reachable through `DerefMut`
+        guard.get_or_insert_with(|| "a".to_owned()).push('b');
+        assert_eq!(guard.as_deref(), Some("ab"));
+        guard.get_or_insert_with(|| unreachable!()).push('c');
+
+        assert_eq!(guard.take().as_deref(), Some("abc"));
+        assert!(guard.is_none());
+
+        guard.insert("x".to_owned()).push('y');
+    });
+    assert_eq!(cell.as_deref(), Some("xy"));
+}
+
+#[test]
+fn write_guard_cmp_value() {
+    with_cryo(&mut 1.5f64, |cryo_mut| {
+        let mut guard = cryo_mut.write();
+        assert!(guard == 1.5);
+        *guard = f64::NAN;
+        assert_eq!(guard.partial_cmp(&1.5), None);
+        *guard = 2.0;
+        assert!(1.5 < guard);
+    });
+}
+
+#[test]
+fn write_guard_op_assign() {
+    use std::ops::{AddAssign, BitOrAssign, ShlAssign, SubAssign};
+
+    fn accumulate<A: AddAssign<u64> + SubAssign<u64>>(acc: &mut A, items: &[u64]) {
+        for &x in items {
+            *acc += x;
+        }
+        *acc -= 1;
+    }
+
+    fn set_flags<A: BitOrAssign<u8> + ShlAssign<u32>>(acc: &mut A) {
+        *acc <<= 1;
+        *acc |= 1;
+    }
+
+    let mut total = 0u64;
+    let mut flags = 0b10u8;
+    with_cryo(&mut total, |cryo_mut| {
+        let mut guard = cryo_mut.write();
+        accumulate(&mut guard, &[1, 2, 3]);
+        guard += 10;
+        guard *= 2;
+        assert_eq!(*guard, 30);
+    });
+    with_cryo(&mut flags, |cryo_mut| set_flags(&mut cryo_mut.write()));
+    assert_eq!(total, 30);
+    assert_eq!(flags, 0b101);
+
+    // Non-`Copy` referents and right-hand sides
+    let mut text = String::from("a");
+    with_cryo(&mut text, |cryo_mut| {
+        let mut guard = cryo_mut.write();
+        guard += "b";
+        guard += &String::from("c");
+    });
+    assert_eq!(text, "abc");
+}
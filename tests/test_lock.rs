@@ -0,0 +1,491 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+#![warn(rust_2018_idioms)]
+
+use cryo::*;
+
+#[cfg(feature = "atomic")]
+#[test]
+fn atomic_lock_static() {
+    static LOCK: AtomicLock = AtomicLock::new();
+    unsafe {
+        assert!(LOCK.try_lock_shared());
+        assert!(!LOCK.try_lock_exclusive());
+        LOCK.unlock_shared();
+        assert!(LOCK.try_lock_exclusive());
+        LOCK.unlock_exclusive();
+    }
+}
+
+#[test]
+fn local_lock_const() {
+    thread_local! {
+        static LOCK: LocalLock = const { LocalLock::new() };
+    }
+    LOCK.with(|lock| unsafe {
+        assert!(lock.try_lock_exclusive());
+        assert!(!lock.try_lock_shared());
+        lock.unlock_exclusive();
+    });
+}
+
+#[test]
+fn sendable_guard() {
+    fn spawn_reader<L: SendableGuard + 'static>(
+        borrow: CryoRef<u32, L>,
+    ) -> std::thread::JoinHandle<u32> {
+        std::thread::spawn(move || *borrow)
+    }
+
+    with_cryo((&42, lock_ty::<SyncLock>()), |cryo| {
+        assert_eq!(spawn_reader(cryo.borrow()).join().unwrap(), 42);
+    });
+    #[cfg(feature = "atomic")]
+    with_cryo((&43, lock_ty::<AtomicLock>()), |cryo| {
+        assert_eq!(spawn_reader(cryo.borrow()).join().unwrap(), 43);
+    });
+}
+
+#[test]
+fn sendable_cryo() {
+    fn read_elsewhere<L: SendableCryo>(cryo_mut: std::pin::Pin<&CryoMut<'_, u32, L>>) -> u32 {
+        std::thread::scope(|s| s.spawn(|| *cryo_mut.read()).join().unwrap())
+    }
+
+    #[cfg(feature = "atomic")]
+    with_cryo((&mut 42, lock_ty::<AtomicLock>()), |cryo_mut| {
+        assert_eq!(read_elsewhere(cryo_mut), 42);
+    });
+    with_cryo((&mut 43, lock_ty::<CondvarLock>()), |cryo_mut| {
+        assert_eq!(read_elsewhere(cryo_mut), 43);
+    });
+}
+
+#[test]
+fn local_lock_clone_shared() {
+    let lock = LocalLock::new();
+    unsafe {
+        assert!(lock.try_lock_shared());
+        for _ in 0..1000 {
+            lock.clone_shared();
+        }
+        assert!(!lock.try_lock_exclusive());
+        for _ in 0..1001 {
+            lock.unlock_shared();
+        }
+        assert!(lock.try_lock_exclusive());
+        lock.unlock_exclusive();
+    }
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn instrumented_lock_counts() {
+    with_cryo(
+        (&mut 0, lock_ty::<InstrumentedLock<LocalLock>>()),
+        |cryo_mut| {
+            let stats = || cryo_mut.raw_lock().stats();
+            assert_eq!(stats(), LockStats::default());
+
+            let borrow = cryo_mut.read();
+            let borrow2 = borrow.clone();
+            assert!(cryo_mut.try_write().is_none());
+            drop((borrow, borrow2));
+            *cryo_mut.write() = 1;
+            assert!(cryo_mut.try_read().is_some());
+
+            assert_eq!(
+                stats(),
+                LockStats {
+                    shared_locks: 3,
+                    exclusive_locks: 1,
+                    contention_events: 1,
+                }
+            );
+        },
+    );
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn instrumented_lock_contention() {
+    with_cryo(
+        (&mut 0, lock_ty::<InstrumentedLock<SyncLock>>()),
+        |cryo_mut| {
+            let borrow = cryo_mut.read();
+            let handle = std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                drop(borrow);
+            });
+            // Blocks until the thread releases `borrow`
+            *cryo_mut.write() = 1;
+            handle.join().unwrap();
+
+            let stats = cryo_mut.raw_lock().stats();
+            assert_eq!(stats.exclusive_locks, 1);
+            assert_eq!(stats.contention_events, 1);
+        },
+    );
+}
+
+#[test]
+fn on_contention() {
+    assert_eq!(LocalLock::ON_CONTENTION, Contention::Panic);
+    assert_eq!(SyncLock::ON_CONTENTION, Contention::Block);
+    assert_eq!(CondvarLock::ON_CONTENTION, Contention::Block);
+    #[cfg(all(feature = "atomic", not(feature = "atomic-block")))]
+    assert_eq!(AtomicLock::ON_CONTENTION, Contention::Panic);
+    #[cfg(feature = "atomic-block")]
+    assert_eq!(AtomicLock::ON_CONTENTION, Contention::Spin);
+    #[cfg(feature = "atomic")]
+    assert_eq!(
+        InstrumentedLock::<SyncLock>::ON_CONTENTION,
+        Contention::Block
+    );
+}
+
+#[cfg(feature = "lock_api")]
+#[test]
+fn on_contention_lock_api() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const EXCLUSIVE: usize = usize::MAX;
+
+    /// A spin-based `RawRwLock`, like the one behind `spin::RwLock`
+    struct SpinRwLock(AtomicUsize);
+
+    unsafe impl lock_api::RawRwLock for SpinRwLock {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INIT: Self = Self(AtomicUsize::new(0));
+        type GuardMarker = lock_api::GuardSend;
+
+        fn lock_shared(&self) {
+            while !lock_api::RawRwLock::try_lock_shared(self) {
+                std::hint::spin_loop();
+            }
+        }
+
+        fn try_lock_shared(&self) -> bool {
+            let count = self.0.load(Ordering::Relaxed);
+            count < EXCLUSIVE - 1
+                && self
+                    .0
+                    .compare_exchange(count, count + 1, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+        }
+
+        unsafe fn unlock_shared(&self) {
+            self.0.fetch_sub(1, Ordering::Release);
+        }
+
+        fn lock_exclusive(&self) {
+            while !lock_api::RawRwLock::try_lock_exclusive(self) {
+                std::hint::spin_loop();
+            }
+        }
+
+        fn try_lock_exclusive(&self) -> bool {
+            self.0
+                .compare_exchange(0, EXCLUSIVE, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        }
+
+        unsafe fn unlock_exclusive(&self) {
+            self.0.store(0, Ordering::Release);
+        }
+    }
+
+    // `lock_api` doesn't expose the waiting strategy
+    assert_eq!(<SpinRwLock as Lock>::ON_CONTENTION, Contention::Block);
+
+    with_cryo((&mut 1, lock_ty::<SpinRwLock>()), |cryo_mut| {
+        *cryo_mut.write() += 1;
+        assert_eq!(*cryo_mut.read(), 2);
+    });
+}
+
+#[test]
+fn on_contention_select_path() {
+    /// Increments the referent, falling back to `None` instead of panicking
+    fn increment<L: Lock>(cryo_mut: std::pin::Pin<&CryoMut<'_, u32, L>>) -> Option<u32> {
+        let mut guard = match L::ON_CONTENTION {
+            Contention::Panic => cryo_mut.try_write()?,
+            Contention::Block | Contention::Spin => cryo_mut.write(),
+        };
+        *guard += 1;
+        Some(*guard)
+    }
+
+    with_cryo(&mut 0, |cryo_mut| {
+        let borrow = cryo_mut.read();
+        assert_eq!(increment(cryo_mut), None);
+        drop(borrow);
+        assert_eq!(increment(cryo_mut), Some(1));
+    });
+    with_cryo((&mut 0, lock_ty::<SyncLock>()), |cryo_mut| {
+        assert_eq!(increment(cryo_mut), Some(1));
+    });
+}
+
+#[test]
+fn local_lock_debug() {
+    let lock = LocalLock::new();
+    assert_eq!(format!("{:?}", lock), "LocalLock { num_shared_locks: 0 }");
+    unsafe {
+        lock.lock_shared();
+        lock.lock_shared();
+        assert_eq!(format!("{:?}", lock), "LocalLock { num_shared_locks: 2 }");
+        lock.unlock_shared();
+        lock.unlock_shared();
+        lock.lock_exclusive();
+        assert_eq!(format!("{:?}", lock), "LocalLock { <locked exclusively> }");
+        lock.unlock_exclusive();
+    }
+    assert_eq!(format!("{:?}", lock), "LocalLock { num_shared_locks: 0 }");
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn atomic_lock_debug() {
+    let lock = AtomicLock::new();
+    assert_eq!(format!("{:?}", lock), "AtomicLock { num_shared_locks: 0 }");
+    unsafe {
+        lock.lock_shared();
+        assert_eq!(format!("{:?}", lock), "AtomicLock { num_shared_locks: 1 }");
+        lock.unlock_shared();
+        lock.lock_exclusive();
+        assert_eq!(format!("{:?}", lock), "AtomicLock { <locked exclusively> }");
+        lock.unlock_exclusive();
+    }
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn atomic_lock_n_limit() {
+    let lock = AtomicLockN::<2>::new();
+    unsafe {
+        assert!(lock.try_lock_shared());
+        assert!(lock.try_lock_shared());
+        assert!(!lock.try_lock_shared());
+        assert_eq!(
+            format!("{:?}", lock),
+            "AtomicLockN<2> { num_shared_locks: 2 }"
+        );
+
+        lock.unlock_shared();
+        assert!(lock.try_lock_shared());
+        lock.unlock_shared();
+        lock.unlock_shared();
+        assert!(lock.try_lock_exclusive());
+        lock.unlock_exclusive();
+    }
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn atomic_lock_n_borrow_limit() {
+    with_cryo((&42, lock_ty::<AtomicLockN<1>>()), |cryo| {
+        let borrow1 = cryo.try_borrow().unwrap();
+        assert!(cryo.try_borrow().is_none());
+        drop(borrow1);
+        assert_eq!(*cryo.try_borrow().unwrap(), 42);
+    });
+}
+
+#[cfg(all(feature = "atomic", not(feature = "atomic-block")))]
+#[test]
+#[should_panic]
+fn atomic_lock_n_lock_shared_over_limit() {
+    let lock = AtomicLockN::<1>::new();
+    unsafe {
+        lock.lock_shared();
+        lock.lock_shared();
+    }
+}
+
+#[test]
+fn num_shared_locks() {
+    fn check<L: Lock>(lock: L) {
+        unsafe {
+            assert_eq!(lock.num_shared_locks(), Some(0));
+            lock.lock_shared();
+            lock.lock_shared();
+            assert_eq!(lock.num_shared_locks(), Some(2));
+            lock.unlock_shared();
+            lock.unlock_shared();
+            lock.lock_exclusive();
+            assert_eq!(lock.num_shared_locks(), Some(0));
+            lock.unlock_exclusive();
+        }
+    }
+    check(LocalLock::new());
+    #[cfg(feature = "atomic")]
+    check(AtomicLock::new());
+    #[cfg(feature = "atomic")]
+    check(InstrumentedLock::with_inner(AtomicLock::new()));
+
+    assert_eq!(SyncLock::new().num_shared_locks(), None);
+}
+
+#[test]
+fn default_lock_methods_spin() {
+    use std::sync::{
+        atomic::{AtomicIsize, Ordering},
+        Arc, Barrier,
+    };
+
+    /// A toy lock that only implements the `try_*` methods
+    struct ToyLock(AtomicIsize);
+
+    unsafe impl Lock for ToyLock {
+        type LockMarker = SendMarker;
+        type UnlockMarker = SendMarker;
+        const ON_CONTENTION: Contention = Contention::Spin;
+
+        fn new() -> Self {
+            Self(AtomicIsize::new(0))
+        }
+        unsafe fn try_lock_shared(&self) -> bool {
+            let count = self.0.load(Ordering::Relaxed);
+            count >= 0
+                && self
+                    .0
+                    .compare_exchange(count, count + 1, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+        }
+        unsafe fn unlock_shared(&self) {
+            self.0.fetch_sub(1, Ordering::Release);
+        }
+        unsafe fn try_lock_exclusive(&self) -> bool {
+            self.0
+                .compare_exchange(0, -1, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        }
+        unsafe fn unlock_exclusive(&self) {
+            self.0.store(0, Ordering::Release);
+        }
+    }
+
+    let mut cell = 0;
+    with_cryo((&mut cell, lock_ty::<ToyLock>()), |cryo_mut| {
+        let barrier = Arc::new(Barrier::new(2));
+        let guard = cryo_mut.write();
+        let thread = {
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                let mut guard = guard;
+                barrier.wait();
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                *guard = 1;
+            })
+        };
+        barrier.wait();
+
+        // `lock_shared` spins until the writer is done
+        assert_eq!(*cryo_mut.read(), 1);
+        *cryo_mut.write() += 1;
+        thread.join().unwrap();
+    });
+    // `lock_exclusive` in the destructor succeeds right away
+    assert_eq!(cell, 2);
+}
+
+#[test]
+fn sync_lock_contention_snapshot() {
+    let state = |num_shared_locks, exclusive, has_waiters| {
+        Some(LockState {
+            num_shared_locks,
+            exclusive,
+            has_waiters,
+        })
+    };
+
+    let lock = SyncLock::new();
+    assert_eq!(lock.contention_snapshot(), state(0, false, false));
+    unsafe {
+        lock.lock_shared();
+        lock.lock_shared();
+        assert_eq!(lock.contention_snapshot(), state(2, false, false));
+        lock.unlock_shared();
+        lock.unlock_shared();
+
+        lock.lock_exclusive();
+        assert_eq!(lock.contention_snapshot(), state(0, true, false));
+        lock.unlock_exclusive();
+    }
+
+    // The creator thread parks in `lock_exclusive` waiting for a shared lock
+    // released by another thread
+    std::thread::scope(|s| {
+        unsafe { lock.lock_shared() };
+        s.spawn(|| {
+            while lock.contention_snapshot() != state(1, false, true) {
+                std::thread::yield_now();
+            }
+            unsafe { lock.unlock_shared() };
+        });
+        unsafe { lock.lock_exclusive() };
+        assert_eq!(lock.contention_snapshot(), state(0, true, false));
+        unsafe { lock.unlock_exclusive() };
+    });
+
+    // The creator thread parks in `lock_shared` waiting for an exclusive lock
+    // released by another thread
+    std::thread::scope(|s| {
+        unsafe { lock.lock_exclusive() };
+        s.spawn(|| {
+            while lock.contention_snapshot() != state(0, true, true) {
+                std::thread::yield_now();
+            }
+            unsafe { lock.unlock_exclusive() };
+        });
+        unsafe { lock.lock_shared() };
+        assert_eq!(lock.contention_snapshot(), state(1, false, false));
+        unsafe { lock.unlock_shared() };
+    });
+}
+
+#[test]
+fn contention_snapshot() {
+    fn check<L: Lock>(lock: L) {
+        unsafe {
+            lock.lock_shared();
+            let state = lock.contention_snapshot().unwrap();
+            assert_eq!((state.num_shared_locks, state.exclusive), (1, false));
+            lock.unlock_shared();
+            lock.lock_exclusive();
+            let state = lock.contention_snapshot().unwrap();
+            assert_eq!((state.num_shared_locks, state.exclusive), (0, true));
+            lock.unlock_exclusive();
+            assert_eq!(lock.contention_snapshot(), Some(LockState::default()));
+        }
+    }
+    check(LocalLock::new());
+    #[cfg(feature = "atomic")]
+    check(AtomicLock::new());
+    check(CondvarLock::new());
+    #[cfg(feature = "atomic")]
+    check(InstrumentedLock::with_inner(LocalLock::new()));
+}
+
+#[test]
+fn cryo_mut_lock_state() {
+    with_cryo((&mut 0, lock_ty::<SyncLock>()), |cryo_mut| {
+        let _guard = cryo_mut.write();
+        assert_eq!(
+            cryo_mut.lock_state(),
+            Some(LockState {
+                num_shared_locks: 0,
+                exclusive: true,
+                has_waiters: false,
+            })
+        );
+    });
+}
@@ -0,0 +1,50 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+#![warn(rust_2018_idioms)]
+
+use cryo::prelude::*;
+
+#[test]
+fn core_items() {
+    let mut cell = 0;
+    with_cryo(
+        &mut cell,
+        |cryo_mut: std::pin::Pin<&CryoMut<'_, i32, LocalLock>>| {
+            let _: CryoMutWriteGuard<i32, _> = cryo_mut.write();
+        },
+    );
+    (&cell, lock_ty::<LocalLock>()).with_cryo(|cryo: std::pin::Pin<&Cryo<'_, i32, _>>| {
+        let borrow: CryoRef<i32, _> = cryo.borrow();
+        let _: &CryoMutReadGuard<i32, _> = &borrow;
+    });
+    let _ = CryoCell::<i32, LocalLock>::new(0);
+    fn assert_lock<L: Lock>() {}
+    assert_lock::<LocalLock>();
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn atomic_items() {
+    with_cryo((&0, lock_ty::<AtomicLock>()), |cryo| *cryo.borrow());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn std_items() {
+    with_cryo((&0, lock_ty::<SyncLock>()), |cryo| *cryo.borrow());
+    with_cryo((&0, lock_ty::<CondvarLock>()), |cryo| *cryo.borrow());
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn arc_items() {
+    let cryo = ArcCryo::<_, AtomicLock>::new(0);
+    let _: ArcCryoRef<i32, _> = cryo.borrow();
+}
@@ -0,0 +1,85 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+//! Pins down the `Send`/`Sync` guarantees of the cell and guard types for
+//! each `Lock` type. The negative cases are in `src/compile_fail_tests.rs`.
+#![warn(rust_2018_idioms)]
+
+use cryo::*;
+
+fn assert_send<T: ?Sized + Send>() {}
+fn assert_sync<T: ?Sized + Sync>() {}
+
+/// The cell types for `Lock`s that can be locked and unlocked by any thread
+fn assert_cells_send_sync<T: ?Sized + Send + Sync + 'static, L: Lock + Send + Sync>()
+where
+    L::LockMarker: Send,
+{
+    assert_send::<Cryo<'static, T, L>>();
+    assert_sync::<Cryo<'static, T, L>>();
+    assert_send::<CryoMut<'static, T, L>>();
+    assert_sync::<CryoMut<'static, T, L>>();
+    assert_send::<CryoCell<T, L>>();
+    assert_sync::<CryoCell<T, L>>();
+}
+
+/// The guard types for `Lock`s that can be unlocked by any thread
+fn assert_guards_send_sync<T: ?Sized + Send + Sync, L: Lock + Sync>()
+where
+    L::UnlockMarker: Send,
+{
+    assert_send::<CryoRef<T, L>>();
+    assert_sync::<CryoRef<T, L>>();
+    assert_send::<CryoMutReadGuard<T, L>>();
+    assert_sync::<CryoMutReadGuard<T, L>>();
+    assert_send::<CryoMutWriteGuard<T, L>>();
+    assert_sync::<CryoMutWriteGuard<T, L>>();
+}
+
+#[test]
+fn local_lock() {
+    // Nothing involving `LocalLock` is `Send`. Guards are `Sync` because
+    // `&CryoRef` only grants `&T`.
+    assert_sync::<CryoRef<u32, LocalLock>>();
+    assert_sync::<CryoMutWriteGuard<u32, LocalLock>>();
+}
+
+#[test]
+fn sync_lock() {
+    // Only the creator thread may lock `SyncLock`, so the cells are neither
+    // `Send` nor `Sync`, but guards can be released by any thread
+    assert_guards_send_sync::<u32, SyncLock>();
+    assert_guards_send_sync::<[String], SyncLock>();
+}
+
+#[test]
+fn condvar_lock() {
+    assert_cells_send_sync::<u32, CondvarLock>();
+    assert_guards_send_sync::<u32, CondvarLock>();
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn atomic_lock() {
+    assert_cells_send_sync::<u32, AtomicLock>();
+    assert_guards_send_sync::<u32, AtomicLock>();
+    assert_cells_send_sync::<String, AtomicLockN<4>>();
+    assert_guards_send_sync::<str, AtomicLockN<4>>();
+    assert_cells_send_sync::<u32, InstrumentedLock<AtomicLock>>();
+}
+
+#[cfg(feature = "atomic")]
+#[test]
+fn referent_bounds() {
+    // `CryoRef<T, _>` is `&T`, so `T: Sync` suffices
+    assert_send::<CryoRef<std::sync::MutexGuard<'static, u32>, AtomicLock>>();
+    assert_send::<Cryo<'static, std::sync::MutexGuard<'static, u32>, AtomicLock>>();
+    // `CryoMutWriteGuard<T, _>` is `&mut T`, so `T: Send` suffices
+    assert_send::<CryoMutWriteGuard<std::cell::Cell<u32>, AtomicLock>>();
+}
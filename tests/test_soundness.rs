@@ -0,0 +1,151 @@
+//
+// Copyright 2018–2021 yvt, all rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+//! Tests exercising the `unsafe` pointer handling of the guard types. These
+//! are intended to be run under Miri:
+//!
+//! ```text
+//! cargo +nightly miri test
+//! ```
+#![warn(rust_2018_idioms)]
+
+use cryo::*;
+use pin_utils::pin_mut;
+
+use std::thread::spawn;
+
+#[test]
+fn clone_drop_in_any_order() {
+    let cell = vec![1, 2, 3];
+    with_cryo(&cell, |cryo| {
+        let b1 = cryo.borrow();
+        let b2 = b1.clone();
+        let b3 = cryo.borrow();
+        drop(b1);
+        assert_eq!(*b3, [1, 2, 3]);
+        let b4 = b3.clone();
+        drop(b3);
+        drop(b4);
+        assert_eq!(b2.len(), 3);
+    });
+}
+
+#[test]
+fn clone_drop_across_threads() {
+    let cell = vec![1, 2, 3];
+    with_cryo((&cell, lock_ty::<SyncLock>()), |cryo| {
+        let b1 = cryo.borrow();
+        let handles: Vec<_> = (0..3)
+            .map(|i| {
+                let b = b1.clone();
+                spawn(move || {
+                    let b2 = b.clone();
+                    drop(b);
+                    b2[i]
+                })
+            })
+            .collect();
+        drop(b1);
+        let sum: i32 = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(sum, 6);
+    });
+}
+
+#[test]
+fn write_then_read() {
+    let mut cell = vec![1];
+    with_cryo(&mut cell, |cryo_mut| {
+        {
+            let mut w = cryo_mut.write();
+            w.push(2);
+            // Reborrow through `Deref` and `DerefMut` alternately
+            assert_eq!(w.len(), 2);
+            w.push(3);
+            assert_eq!(w.len(), 3);
+        }
+        let r1 = cryo_mut.read();
+        let r2 = cryo_mut.read();
+        assert_eq!(*r1, *r2);
+        drop(r1);
+        drop(r2);
+        cryo_mut.write().push(4);
+    });
+    assert_eq!(cell, [1, 2, 3, 4]);
+}
+
+#[test]
+fn write_across_threads() {
+    let mut cell = 0u32;
+    with_cryo((&mut cell, lock_ty::<SyncLock>()), |cryo_mut| {
+        for _ in 0..4 {
+            let mut w = cryo_mut.write();
+            spawn(move || *w += 1);
+        }
+    });
+    assert_eq!(cell, 4);
+}
+
+#[test]
+fn projection() {
+    let mut cell = Some(Box::new(String::from("héllo")));
+    with_cryo(&mut cell, |cryo_mut| {
+        let s = cryo_mut
+            .read()
+            .transpose()
+            .unwrap()
+            .flatten()
+            .deref_into()
+            .slice(3..6)
+            .unwrap();
+        let s2 = s.clone();
+        drop(s);
+        assert_eq!(&*s2, "llo");
+        assert!(cryo_mut.try_write().is_none());
+        drop(s2);
+
+        let mut w = cryo_mut.write().transpose().unwrap();
+        w.push('!');
+        drop(w);
+        assert_eq!(**cryo_mut.read().transpose().unwrap(), "héllo!");
+    });
+}
+
+#[test]
+fn unsized_referent() {
+    let mut cell = [1u8, 2, 3];
+    with_cryo(&mut cell[..], |cryo_mut| {
+        cryo_mut.write()[0] = 4;
+        assert_eq!(*cryo_mut.read(), [4, 2, 3]);
+    });
+}
+
+#[test]
+fn cryo_cell_read_write() {
+    let cell: CryoCell<_, LocalLock> = CryoCell::new(vec![1]);
+    pin_mut!(cell);
+    let w = cell.as_ref().write();
+    drop(w);
+    let r = cell.as_ref().read();
+    let r2 = r.clone();
+    drop(r);
+    assert_eq!(*r2, [1]);
+    drop(r2);
+    cell.as_ref().write().push(2);
+    assert_eq!(*cell.as_ref().read(), [1, 2]);
+}
+
+#[cfg(all(feature = "alloc", feature = "atomic"))]
+#[test]
+fn read_arc_outlives_cell_handle() {
+    let cell = std::sync::Arc::pin(CryoCell::<_, AtomicLock>::new(String::from("a")));
+    let r = cell.read_arc();
+    drop(cell);
+    let h = spawn(move || r.len());
+    assert_eq!(h.join().unwrap(), 1);
+}